@@ -0,0 +1,95 @@
+//! `#[derive(BitIndexable)]` for fieldless enums, re-exported by `bit-index` with its `derive` feature.
+//!
+//! Variants map to bits in declaration order. Besides `BitIndexable`, the derive implements
+//! `From<Enum>` for every `BitIndex` width that can keep all variants.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident};
+
+const WIDTHS: [(u16, &str); 5] = [
+    (8, "BitIndex8"),
+    (16, "BitIndex16"),
+    (32, "BitIndex32"),
+    (64, "BitIndex64"),
+    (128, "BitIndex128"),
+];
+
+#[proc_macro_derive(BitIndexable)]
+pub fn derive_bit_indexable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "BitIndexable can only be derived for enums",
+            ))
+        }
+    };
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|v| !matches!(v.fields, Fields::Unit))
+    {
+        return Err(Error::new_spanned(
+            variant,
+            "BitIndexable variants cannot have fields",
+        ));
+    }
+    if data.variants.len() > 128 {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "BitIndexable enums can have at most 128 variants",
+        ));
+    }
+
+    let nb_variants = data.variants.len() as u8;
+    let variants: Vec<_> = data.variants.iter().map(|v| &v.ident).collect();
+    let bit_nbs: Vec<_> = (0..nb_variants).collect();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let from_impls = WIDTHS
+        .iter()
+        .filter(|(size, _)| nb_variants as u16 <= *size)
+        .map(|(_, width)| {
+            let width = Ident::new(width, Span::call_site());
+            quote! {
+                impl #impl_generics ::core::convert::From<#name #ty_generics> for ::bit_index::#width #where_clause {
+                    fn from(variant: #name #ty_generics) -> Self {
+                        // cannot fail: only derived for widths that keep all variants
+                        ::bit_index::#width::from_variant(variant).unwrap()
+                    }
+                }
+            }
+        });
+
+    Ok(quote! {
+        impl #impl_generics ::bit_index::BitIndexable for #name #ty_generics #where_clause {
+            const NB_VARIANTS: u8 = #nb_variants;
+
+            fn bit_nb(self) -> u8 {
+                match self {
+                    #(#name::#variants => #bit_nbs,)*
+                }
+            }
+
+            fn from_bit_nb(bit_nb: u8) -> ::core::option::Option<Self> {
+                match bit_nb {
+                    #(#bit_nbs => ::core::option::Option::Some(#name::#variants),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+
+        #(#from_impls)*
+    })
+}
@@ -0,0 +1,42 @@
+use bit_index::{BitIndex128, BitIndex16, BitIndex8, BitIndexable};
+
+#[derive(BitIndexable, Copy, Clone, Debug, PartialEq)]
+enum Piece {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+#[test]
+fn bit_nbs() {
+    assert_eq!(6, Piece::NB_VARIANTS);
+    assert_eq!(0, Piece::Pawn.bit_nb());
+    assert_eq!(5, Piece::King.bit_nb());
+    assert_eq!(Some(Piece::Rook), Piece::from_bit_nb(3));
+    assert_eq!(None, Piece::from_bit_nb(6));
+}
+
+#[test]
+fn from_variant() {
+    let bi = BitIndex8::from(Piece::Knight);
+    assert_eq!(0b10, bi.unwrap());
+    assert_eq!(6, bi.nb_bits());
+    assert_eq!(0b1000, BitIndex128::from(Piece::Rook).unwrap());
+}
+
+#[test]
+fn typed_queries() {
+    let mut bi = BitIndex16::from(Piece::Queen);
+    bi.insert_variant(Piece::Pawn);
+    bi.insert_variant(Piece::King);
+    assert!(bi.contains_variant(Piece::Queen));
+    assert!(!bi.contains_variant(Piece::Bishop));
+    bi.remove_variant(Piece::Queen);
+    assert_eq!(
+        vec![Piece::Pawn, Piece::King],
+        bi.variants::<Piece>().collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,256 @@
+use crate::BitIndex64;
+
+/// A reference to a row of a `BitSetArena`. It goes stale once its row is removed,
+/// even if the slot is reused by a later insertion.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ArenaHandle {
+    slot: u32,
+    generation: u32,
+}
+
+/// Many `BitIndex64` rows of the same `nb_bits`, stored contiguously and addressed by handle.
+///
+/// The whole-arena operations are branch-free loops over the row slice, which LLVM vectorizes.
+/// Slots of removed rows are kept empty until reused, so `rows` can be fed to the reductions
+/// in `bulk` directly; they only show up there as empty masks.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BitSetArena {
+    nb_bits: u8,
+    rows: Vec<BitIndex64>,
+    // all ones for a live slot, zero for a free one
+    live: Vec<u64>,
+    generations: Vec<u32>,
+    free: Vec<u32>,
+}
+
+impl BitSetArena {
+    /// An arena of rows with `nb_bits` bits each.
+    pub fn new(nb_bits: u8) -> Result<Self, String> {
+        BitIndex64::empty(nb_bits).map(|_| Self {
+            nb_bits,
+            rows: Vec::new(),
+            live: Vec::new(),
+            generations: Vec::new(),
+            free: Vec::new(),
+        })
+    }
+
+    pub fn nb_bits(&self) -> u8 {
+        self.nb_bits
+    }
+
+    /// The number of live rows.
+    pub fn len(&self) -> usize {
+        self.rows.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every slot, free ones included as empty rows.
+    pub fn rows(&self) -> &[BitIndex64] {
+        &self.rows
+    }
+
+    /// Adds an empty row.
+    pub fn insert_empty(&mut self) -> ArenaHandle {
+        self.insert(BitIndex64::empty(self.nb_bits).unwrap())
+    }
+
+    /// Adds `row`, which must have the `nb_bits` of the arena.
+    pub fn insert(&mut self, row: BitIndex64) -> ArenaHandle {
+        if row.nb_bits != self.nb_bits {
+            panic!(
+                "This BitSetArena keeps rows of {} bits, not {}",
+                self.nb_bits, row.nb_bits
+            );
+        }
+        match self.free.pop() {
+            Some(slot) => {
+                self.rows[slot as usize] = row;
+                self.live[slot as usize] = u64::MAX;
+                ArenaHandle {
+                    slot,
+                    generation: self.generations[slot as usize],
+                }
+            }
+            None => {
+                self.rows.push(row);
+                self.live.push(u64::MAX);
+                self.generations.push(0);
+                ArenaHandle {
+                    slot: self.rows.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Removes the row and returns it, or `None` for a stale handle.
+    pub fn remove(&mut self, handle: ArenaHandle) -> Option<BitIndex64> {
+        let slot = self.slot(handle)?;
+        let row = self.rows[slot];
+        self.rows[slot].clear();
+        self.live[slot] = 0;
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        self.free.push(slot as u32);
+        Some(row)
+    }
+
+    pub fn get(&self, handle: ArenaHandle) -> Option<&BitIndex64> {
+        self.slot(handle).map(|slot| &self.rows[slot])
+    }
+
+    pub fn get_mut(&mut self, handle: ArenaHandle) -> Option<&mut BitIndex64> {
+        self.slot(handle).map(move |slot| &mut self.rows[slot])
+    }
+
+    /// The live rows with their handles, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (ArenaHandle, &BitIndex64)> {
+        self.rows
+            .iter()
+            .zip(self.generations.iter())
+            .enumerate()
+            .filter(move |(slot, _)| self.live[*slot] != 0)
+            .map(|(slot, (row, &generation))| {
+                (
+                    ArenaHandle {
+                        slot: slot as u32,
+                        generation,
+                    },
+                    row,
+                )
+            })
+    }
+
+    /// Unsets every bit of every row.
+    pub fn clear_all(&mut self) {
+        for row in self.rows.iter_mut() {
+            row.bits = 0;
+        }
+    }
+
+    /// Sets all `nb_bits` bits of every live row.
+    pub fn restore_all(&mut self) {
+        let full = BitIndex64::new(self.nb_bits).unwrap().bits;
+        for (row, &live) in self.rows.iter_mut().zip(self.live.iter()) {
+            row.bits = full & live;
+        }
+    }
+
+    /// Unsets `bit_nb` in every row.
+    pub fn clear_column(&mut self, bit_nb: u8) {
+        let keep = !Self::column(self.nb_bits, bit_nb);
+        for row in self.rows.iter_mut() {
+            row.bits &= keep;
+        }
+    }
+
+    /// Sets `bit_nb` in every live row.
+    pub fn set_column(&mut self, bit_nb: u8) {
+        let column = Self::column(self.nb_bits, bit_nb);
+        for (row, &live) in self.rows.iter_mut().zip(self.live.iter()) {
+            row.bits |= column & live;
+        }
+    }
+
+    /// Adds the elements of `mask` to every live row.
+    pub fn or_all(&mut self, mask: &BitIndex64) {
+        let bits = mask.bits & BitIndex64::new(self.nb_bits).unwrap().bits;
+        for (row, &live) in self.rows.iter_mut().zip(self.live.iter()) {
+            row.bits |= bits & live;
+        }
+    }
+
+    /// Keeps only the elements of `mask` in every row.
+    pub fn and_all(&mut self, mask: &BitIndex64) {
+        for row in self.rows.iter_mut() {
+            row.bits &= mask.bits;
+        }
+    }
+
+    fn slot(&self, handle: ArenaHandle) -> Option<usize> {
+        let slot = handle.slot as usize;
+        match self.generations.get(slot) {
+            Some(&generation) if generation == handle.generation && self.live[slot] != 0 => {
+                Some(slot)
+            }
+            _ => None,
+        }
+    }
+
+    fn column(nb_bits: u8, bit_nb: u8) -> u64 {
+        if bit_nb >= nb_bits {
+            panic!("This BitSetArena can only handle inputs upto {}", nb_bits);
+        }
+        1 << bit_nb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bulk;
+
+    #[test]
+    fn handles() {
+        let mut arena = BitSetArena::new(10).unwrap();
+        let a = arena.insert_empty();
+        let mut row = BitIndex64::empty(10).unwrap();
+        row.set_bits(&[1, 9]);
+        let b = arena.insert(row);
+        assert_eq!(2, arena.len());
+        assert_eq!(Some(&row), arena.get(b));
+
+        arena.get_mut(a).unwrap().set_bit(4);
+        assert_eq!(Some(4), arena.get(a).unwrap().smallest());
+
+        assert_eq!(Some(row), arena.remove(b));
+        assert_eq!(None, arena.get(b));
+        assert_eq!(None, arena.remove(b));
+
+        // the slot is reused, the old handle stays stale
+        let c = arena.insert_empty();
+        assert_eq!(2, arena.rows().len());
+        assert_eq!(None, arena.get(b));
+        assert!(arena.get(c).unwrap().is_empty());
+        assert_eq!(vec![a, c], arena.iter().map(|(h, _)| h).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn bulk_ops() {
+        let mut arena = BitSetArena::new(8).unwrap();
+        let handles: Vec<_> = (0..100).map(|_| arena.insert_empty()).collect();
+        for &h in handles.iter().step_by(10) {
+            arena.remove(h);
+        }
+
+        arena.restore_all();
+        assert_eq!(90 * 8, bulk::total_count(arena.rows()));
+        arena.clear_column(3);
+        assert_eq!(90 * 7, bulk::total_count(arena.rows()));
+        assert_eq!(0, bulk::column_histogram(arena.rows())[3]);
+
+        let mut mask = BitIndex64::empty(8).unwrap();
+        mask.set_bits(&[0, 3]);
+        arena.and_all(&mask);
+        assert_eq!(90, bulk::total_count(arena.rows()));
+        arena.or_all(&mask);
+        arena.set_column(7);
+        assert_eq!(90 * 3, bulk::total_count(arena.rows()));
+        assert!(arena.iter().all(|(_, row)| row.unwrap() == 0b1000_1001));
+
+        arena.clear_all();
+        assert_eq!(0, bulk::total_count(arena.rows()));
+        assert!(BitSetArena::new(65).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_width() {
+        BitSetArena::new(8)
+            .unwrap()
+            .insert(BitIndex64::empty(9).unwrap());
+    }
+}
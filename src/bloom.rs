@@ -0,0 +1,94 @@
+use crate::BitIndex;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A Bloom filter using a `BitIndex` as its bit array.
+///
+/// The `nb_hashes` positions of an item are derived from a single 64-bit hash by double hashing.
+/// `maybe_contains` never gives false negatives; false positives grow as the index fills up.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BloomIndex<B> {
+    bits: B,
+    nb_hashes: u8,
+}
+
+impl<B: BitIndex> BloomIndex<B> {
+    pub fn new(nb_bits: u8, nb_hashes: u8) -> Result<Self, String> {
+        if nb_bits == 0 || nb_hashes == 0 {
+            return Err(format!(
+                "A BloomIndex needs at least one bit and one hash function, not {} and {}",
+                nb_bits, nb_hashes
+            ));
+        }
+        B::empty(nb_bits).map(|bits| Self { bits, nb_hashes })
+    }
+
+    pub fn nb_hashes(&self) -> u8 {
+        self.nb_hashes
+    }
+
+    /// The underlying bit array.
+    pub fn bits(&self) -> B {
+        self.bits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        for bit_nb in self.positions(item) {
+            self.bits.set_bit(bit_nb);
+        }
+    }
+
+    pub fn maybe_contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        self.positions(item)
+            .all(|bit_nb| self.bits.contains(bit_nb))
+    }
+
+    fn positions<T: Hash + ?Sized>(&self, item: &T) -> impl Iterator<Item = u8> {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+        let (h1, h2) = (hash & 0xFFFF_FFFF, (hash >> 32) | 1);
+        let nb_bits = self.bits.nb_bits() as u64;
+        (0..self.nb_hashes as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % nb_bits) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitIndex128, BitIndex64};
+
+    #[test]
+    fn insert_contains() {
+        let mut bloom = BloomIndex::<BitIndex128>::new(128, 3).unwrap();
+        assert!(bloom.is_empty());
+        let words = ["knight", "bishop", "rook", "queen"];
+        for word in words.iter() {
+            bloom.insert(*word);
+        }
+        assert!(words.iter().all(|word| bloom.maybe_contains(*word)));
+        assert!(bloom.bits().nb_elements() <= 12);
+        let false_positives = (0..100u32).filter(|i| bloom.maybe_contains(i)).count();
+        assert!(false_positives < 10);
+
+        bloom.clear();
+        assert!(!bloom.maybe_contains("rook"));
+    }
+
+    #[test]
+    fn new() {
+        assert!(BloomIndex::<BitIndex64>::new(65, 2).is_err());
+        assert!(BloomIndex::<BitIndex64>::new(0, 2).is_err());
+        assert!(BloomIndex::<BitIndex64>::new(64, 0).is_err());
+        assert_eq!(4, BloomIndex::<BitIndex64>::new(40, 4).unwrap().nb_hashes());
+    }
+}
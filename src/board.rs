@@ -0,0 +1,303 @@
+//! Bitboards: a `BitIndex64` with 64 bits as an 8 × 8 board, enabled with the `board` feature.
+//!
+//! Square `(file, rank)` is bit `file + 8 * rank`, so a1 is bit 0, h1 bit 7 and h8 bit 63.
+//! Rotations are seen from above with rank 1 at the bottom.
+//!
+//! The attack functions return the squares a piece on `square` attacks. Knights, kings and
+//! pawns are looked up in tables built at compile time; sliding pieces are computed with
+//! shift fills that stop at the first occupied square in each direction.
+
+use crate::BitIndex64;
+
+/// The side a pawn plays for: white pawns attack towards rank 8, black pawns towards rank 1.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Side {
+    White,
+    Black,
+}
+
+const NOT_FILE_A: u64 = !0x0101_0101_0101_0101;
+const NOT_FILE_H: u64 = !0x8080_8080_8080_8080;
+
+// (shift, mask of the squares that can be reached without wrapping around a file edge)
+const NORTH: (i8, u64) = (8, !0);
+const SOUTH: (i8, u64) = (-8, !0);
+const EAST: (i8, u64) = (1, NOT_FILE_A);
+const WEST: (i8, u64) = (-1, NOT_FILE_H);
+const NORTH_EAST: (i8, u64) = (9, NOT_FILE_A);
+const NORTH_WEST: (i8, u64) = (7, NOT_FILE_H);
+const SOUTH_EAST: (i8, u64) = (-7, NOT_FILE_A);
+const SOUTH_WEST: (i8, u64) = (-9, NOT_FILE_H);
+
+const KNIGHT_ATTACKS: [u64; 64] = leaper_table(&[
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+]);
+const KING_ATTACKS: [u64; 64] = leaper_table(&[
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+]);
+const ORTHOGONAL_NEIGHBORS: [u64; 64] = leaper_table(&[(0, 1), (1, 0), (0, -1), (-1, 0)]);
+const PAWN_ATTACKS: [[u64; 64]; 2] = [
+    leaper_table(&[(-1, 1), (1, 1)]),
+    leaper_table(&[(-1, -1), (1, -1)]),
+];
+
+/// The squares sharing an edge with `square`, or also a corner if `diagonal`.
+pub fn neighbors(square: u8, diagonal: bool) -> BitIndex64 {
+    if diagonal {
+        king_attacks(square)
+    } else {
+        board(ORTHOGONAL_NEIGHBORS[check_square(square)])
+    }
+}
+
+pub fn knight_attacks(square: u8) -> BitIndex64 {
+    board(KNIGHT_ATTACKS[check_square(square)])
+}
+
+pub fn king_attacks(square: u8) -> BitIndex64 {
+    board(KING_ATTACKS[check_square(square)])
+}
+
+/// The squares a pawn of `side` captures on, regardless of what occupies them.
+pub fn pawn_attacks(square: u8, side: Side) -> BitIndex64 {
+    board(PAWN_ATTACKS[side as usize][check_square(square)])
+}
+
+/// The squares a rook attacks, up to and including the first `occupied` square in each direction.
+pub fn rook_attacks(square: u8, occupied: BitIndex64) -> BitIndex64 {
+    slide(square, occupied, &[NORTH, SOUTH, EAST, WEST])
+}
+
+/// The squares a bishop attacks, up to and including the first `occupied` square in each direction.
+pub fn bishop_attacks(square: u8, occupied: BitIndex64) -> BitIndex64 {
+    slide(
+        square,
+        occupied,
+        &[NORTH_EAST, NORTH_WEST, SOUTH_EAST, SOUTH_WEST],
+    )
+}
+
+pub fn queen_attacks(square: u8, occupied: BitIndex64) -> BitIndex64 {
+    board(rook_attacks(square, occupied).bits | bishop_attacks(square, occupied).bits)
+}
+
+impl BitIndex64 {
+    /// Mirrors the ranks: a1 becomes a8.
+    pub fn flip_vertical(&self) -> Self {
+        self.transformed(self.bits.swap_bytes())
+    }
+
+    /// Mirrors the files: a1 becomes h1.
+    pub fn flip_horizontal(&self) -> Self {
+        let bits = delta_swap(self.bits, 0x5555_5555_5555_5555, 1);
+        let bits = delta_swap(bits, 0x3333_3333_3333_3333, 2);
+        self.transformed(delta_swap(bits, 0x0F0F_0F0F_0F0F_0F0F, 4))
+    }
+
+    /// Mirrors along the a1-h8 diagonal: h1 becomes a8.
+    pub fn flip_diagonal(&self) -> Self {
+        let bits = delta_swap(self.bits, 0x0000_0000_F0F0_F0F0, 28);
+        let bits = delta_swap(bits, 0x0000_CCCC_0000_CCCC, 14);
+        self.transformed(delta_swap(bits, 0x00AA_00AA_00AA_00AA, 7))
+    }
+
+    /// Rotates a quarter turn clockwise: a1 becomes a8.
+    pub fn rotate_90(&self) -> Self {
+        self.flip_diagonal().flip_vertical()
+    }
+
+    /// Rotates a half turn: a1 becomes h8.
+    pub fn rotate_180(&self) -> Self {
+        self.transformed(self.bits.reverse_bits())
+    }
+
+    /// Rotates a quarter turn counterclockwise: a1 becomes h1.
+    pub fn rotate_270(&self) -> Self {
+        self.flip_vertical().flip_diagonal()
+    }
+
+    fn transformed(&self, bits: u64) -> Self {
+        if self.nb_bits != 64 {
+            panic!(
+                "Board transforms need all 64 bits, this BitIndex64 has {}",
+                self.nb_bits
+            );
+        }
+        Self { bits, nb_bits: 64 }
+    }
+}
+
+fn check_square(square: u8) -> usize {
+    if square >= 64 {
+        panic!("A board only has squares upto 63, not {}", square);
+    }
+    square as usize
+}
+
+fn board(bits: u64) -> BitIndex64 {
+    BitIndex64 { bits, nb_bits: 64 }
+}
+
+fn slide(square: u8, occupied: BitIndex64, directions: &[(i8, u64)]) -> BitIndex64 {
+    let empty = !occupied.bits;
+    let start = 1 << check_square(square);
+    board(directions.iter().fold(0, |attacks, &direction| {
+        let mut flood = start;
+        let mut ray = start;
+        for _ in 0..6 {
+            ray = shift(ray, direction) & empty;
+            flood |= ray;
+        }
+        attacks | shift(flood, direction)
+    }))
+}
+
+fn shift(bits: u64, (delta, mask): (i8, u64)) -> u64 {
+    let shifted = if delta > 0 {
+        bits << delta
+    } else {
+        bits >> -delta
+    };
+    shifted & mask
+}
+
+const fn leaper_table(offsets: &[(i8, i8)]) -> [u64; 64] {
+    let mut table = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut i = 0;
+        while i < offsets.len() {
+            let file = (square % 8) as i8 + offsets[i].0;
+            let rank = (square / 8) as i8 + offsets[i].1;
+            if file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                table[square] |= 1 << (file + 8 * rank);
+            }
+            i += 1;
+        }
+        square += 1;
+    }
+    table
+}
+
+// Swaps the bits selected by `mask` with the bits `delta` places above them.
+fn delta_swap(bits: u64, mask: u64, delta: u32) -> u64 {
+    let t = ((bits >> delta) ^ bits) & mask;
+    bits ^ t ^ (t << delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(squares: &[(u8, u8)]) -> BitIndex64 {
+        let mut bi = BitIndex64::empty(64).unwrap();
+        for &(file, rank) in squares {
+            bi.set_bit(file + 8 * rank);
+        }
+        bi
+    }
+
+    fn map(bi: BitIndex64, f: impl Fn(u8, u8) -> (u8, u8)) -> BitIndex64 {
+        let mut res = BitIndex64::empty(64).unwrap();
+        for bit_nb in (0..64).filter(|&i| bi.contains(i)) {
+            let (file, rank) = f(bit_nb % 8, bit_nb / 8);
+            res.set_bit(file + 8 * rank);
+        }
+        res
+    }
+
+    #[test]
+    fn corners() {
+        let a1 = board(&[(0, 0)]);
+        assert_eq!(board(&[(0, 7)]), a1.flip_vertical());
+        assert_eq!(board(&[(7, 0)]), a1.flip_horizontal());
+        assert_eq!(board(&[(0, 7)]), board(&[(7, 0)]).flip_diagonal());
+        assert_eq!(board(&[(0, 7)]), a1.rotate_90());
+        assert_eq!(board(&[(7, 7)]), a1.rotate_180());
+        assert_eq!(board(&[(7, 0)]), a1.rotate_270());
+    }
+
+    #[test]
+    fn against_squares() {
+        let bi = board(&[(0, 0), (1, 0), (2, 5), (6, 3), (7, 6), (4, 4), (3, 7)]);
+        assert_eq!(map(bi, |f, r| (f, 7 - r)), bi.flip_vertical());
+        assert_eq!(map(bi, |f, r| (7 - f, r)), bi.flip_horizontal());
+        assert_eq!(map(bi, |f, r| (r, f)), bi.flip_diagonal());
+        assert_eq!(map(bi, |f, r| (r, 7 - f)), bi.rotate_90());
+        assert_eq!(map(bi, |f, r| (7 - f, 7 - r)), bi.rotate_180());
+        assert_eq!(map(bi, |f, r| (7 - r, f)), bi.rotate_270());
+        assert_eq!(bi, bi.rotate_90().rotate_270());
+        assert_eq!(bi.rotate_180(), bi.rotate_90().rotate_90());
+    }
+
+    #[test]
+    fn leapers() {
+        assert_eq!(board(&[(1, 2), (2, 1)]), knight_attacks(0));
+        assert_eq!(8, knight_attacks(3 + 8 * 3).nb_elements());
+        assert_eq!(board(&[(6, 7), (6, 6), (7, 6)]), king_attacks(63));
+        assert_eq!(
+            board(&[(3, 4), (5, 4)]),
+            pawn_attacks(4 + 8 * 3, Side::White)
+        );
+        assert_eq!(board(&[(6, 2)]), pawn_attacks(7 + 8 * 3, Side::Black));
+        assert!(pawn_attacks(60, Side::White).is_empty());
+
+        assert_eq!(board(&[(1, 0), (0, 1)]), neighbors(0, false));
+        assert_eq!(board(&[(1, 0), (0, 1), (1, 1)]), neighbors(0, true));
+        assert_eq!(board(&[(6, 7), (7, 6)]), neighbors(63, false));
+        assert_eq!(4, neighbors(3 + 8 * 3, false).nb_elements());
+        assert_eq!(8, neighbors(3 + 8 * 3, true).nb_elements());
+    }
+
+    #[test]
+    fn sliders() {
+        let empty = BitIndex64::empty(64).unwrap();
+        assert_eq!(14, rook_attacks(27, empty).nb_elements());
+        assert_eq!(7, bishop_attacks(0, empty).nb_elements());
+        assert_eq!(27, queen_attacks(27, empty).nb_elements());
+
+        // rook on a1, blockers on a3 and d1: the blockers are attacked, what is behind is not
+        let occupied = board(&[(0, 2), (3, 0), (0, 5)]);
+        assert_eq!(
+            board(&[(0, 1), (0, 2), (1, 0), (2, 0), (3, 0)]),
+            rook_attacks(0, occupied)
+        );
+
+        // bishop on c1 with a blocker on e3, nothing wraps past the h file
+        let occupied = board(&[(4, 2)]);
+        assert_eq!(
+            board(&[(1, 1), (0, 2), (3, 1), (4, 2)]),
+            bishop_attacks(2, occupied)
+        );
+        assert_eq!(
+            board(&[(6, 1), (5, 2), (4, 3), (3, 4), (2, 5), (1, 6), (0, 7)]),
+            bishop_attacks(7, empty)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn off_board() {
+        knight_attacks(64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn partial_board() {
+        BitIndex64::new(32).unwrap().flip_vertical();
+    }
+}
@@ -0,0 +1,139 @@
+//! Reductions over slices of `BitIndex64`.
+//!
+//! The loops are written so that LLVM vectorizes them. On x86_64 they are also compiled
+//! for AVX2, and with the `avx512` feature for AVX-512 VPOPCNTDQ; the best version is picked
+//! at runtime. The AVX-512 target features need Rust 1.89.
+//! aarch64 always has NEON, which the default build already uses.
+
+use crate::BitIndex64;
+
+/// The distribution of the number of elements per mask.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DensitySummary {
+    pub nb_masks: usize,
+    pub min: u8,
+    pub max: u8,
+    pub mean: f64,
+}
+
+// Defines `$name` dispatching to copies of `$body` compiled for wider vector units.
+macro_rules! multiversion {
+    ($(#[$attr:meta])* pub fn $name:ident($masks:ident: &[BitIndex64]) -> $ret:ty $body:block) => {
+        $(#[$attr])*
+        pub fn $name($masks: &[BitIndex64]) -> $ret {
+            #[cfg(target_arch = "x86_64")]
+            {
+                #[cfg(feature = "avx512")]
+                #[target_feature(enable = "avx512f,avx512vpopcntdq")]
+                unsafe fn avx512($masks: &[BitIndex64]) -> $ret $body
+
+                #[target_feature(enable = "avx2,popcnt")]
+                unsafe fn avx2($masks: &[BitIndex64]) -> $ret $body
+
+                #[cfg(feature = "avx512")]
+                if is_x86_feature_detected!("avx512f")
+                    && is_x86_feature_detected!("avx512vpopcntdq")
+                {
+                    // safe: the required features were detected
+                    return unsafe { avx512($masks) };
+                }
+                if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("popcnt") {
+                    return unsafe { avx2($masks) };
+                }
+            }
+            $body
+        }
+    };
+}
+
+multiversion! {
+    /// The number of elements over all masks.
+    pub fn total_count(masks: &[BitIndex64]) -> u64 {
+        masks.iter().map(|mask| mask.bits.count_ones() as u64).sum()
+    }
+}
+
+multiversion! {
+    /// For every bit position, the number of masks containing it.
+    pub fn column_histogram(masks: &[BitIndex64]) -> [u64; 64] {
+        let mut histogram = [0; 64];
+        for mask in masks {
+            for (bit_nb, count) in histogram.iter_mut().enumerate() {
+                *count += mask.bits >> bit_nb & 1;
+            }
+        }
+        histogram
+    }
+}
+
+multiversion! {
+    /// Summary of the number of elements per mask, `None` for no masks.
+    pub fn density_summary(masks: &[BitIndex64]) -> Option<DensitySummary> {
+        if masks.is_empty() {
+            return None;
+        }
+        let (mut min, mut max, mut total) = (u32::MAX, 0, 0u64);
+        for mask in masks {
+            let count = mask.bits.count_ones();
+            min = min.min(count);
+            max = max.max(count);
+            total += count as u64;
+        }
+        Some(DensitySummary {
+            nb_masks: masks.len(),
+            min: min as u8,
+            max: max as u8,
+            mean: total as f64 / masks.len() as f64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift;
+    use std::convert::TryFrom;
+
+    fn masks() -> Vec<BitIndex64> {
+        let mut next = xorshift(0x2545_F491_4F6C_DD1D);
+        (0..1000)
+            .map(|_| {
+                let x = next();
+                BitIndex64::try_from((x & x >> 3) as u128).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn total() {
+        let masks = masks();
+        let naive: u64 = masks.iter().map(|mask| mask.nb_elements() as u64).sum();
+        assert_eq!(naive, total_count(&masks));
+        assert_eq!(0, total_count(&[]));
+    }
+
+    #[test]
+    fn histogram() {
+        let masks = masks();
+        let histogram = column_histogram(&masks);
+        for (bit_nb, &count) in histogram.iter().enumerate() {
+            let naive = masks
+                .iter()
+                .filter(|mask| mask.contains(bit_nb as u8))
+                .count();
+            assert_eq!(naive as u64, count);
+        }
+        assert_eq!(total_count(&masks), histogram.iter().sum::<u64>());
+    }
+
+    #[test]
+    fn density() {
+        let mut masks = vec![BitIndex64::new(10).unwrap(), BitIndex64::new(4).unwrap()];
+        masks.push(BitIndex64::empty(64).unwrap());
+        let summary = density_summary(&masks).unwrap();
+        assert_eq!(3, summary.nb_masks);
+        assert_eq!((0, 10), (summary.min, summary.max));
+        assert!((summary.mean - 14.0 / 3.0).abs() < 1e-9);
+        assert_eq!(None, density_summary(&[]));
+    }
+}
@@ -0,0 +1,105 @@
+//! Candidate tracking for constraint solvers, where each cell keeps the values it can still take.
+//!
+//! A Sudoku cell is typically a `BitIndex16` with `nb_bits` 9, bit `i` standing for digit `i + 1`.
+
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+
+macro_rules! impl_candidates {
+    ($bit_index_name:ident) => {
+        impl $bit_index_name {
+            /// The candidate if exactly one is left.
+            pub fn only_candidate(&self) -> Option<u8> {
+                if self.bits.count_ones() == 1 {
+                    Some(self.bits.trailing_zeros() as u8)
+                } else {
+                    None
+                }
+            }
+
+            /// Removes the candidates in `mask` and returns whether any were present.
+            pub fn eliminate(&mut self, mask: &Self) -> bool {
+                let removed = self.bits & mask.bits;
+                self.bits ^= removed;
+                removed != 0
+            }
+
+            /// The candidate that none of the `others` in the same unit can take, if there is exactly one.
+            pub fn hidden_single(&self, others: &[Self]) -> Option<u8> {
+                let taken = others.iter().fold(0, |acc, other| acc | other.bits);
+                Self {
+                    bits: self.bits & !taken,
+                    nb_bits: self.nb_bits,
+                }
+                .only_candidate()
+            }
+
+            /// The positions `(i, j)`, `i < j`, of cells that have the same two candidates left.
+            /// Those two candidates can be eliminated from the rest of the unit.
+            pub fn naked_pairs(cells: &[Self]) -> Vec<(usize, usize)> {
+                let mut pairs = Vec::new();
+                for (i, a) in cells.iter().enumerate() {
+                    if a.bits.count_ones() != 2 {
+                        continue;
+                    }
+                    for (j, b) in cells.iter().enumerate().skip(i + 1) {
+                        if a.bits == b.bits {
+                            pairs.push((i, j));
+                        }
+                    }
+                }
+                pairs
+            }
+        }
+    };
+}
+
+impl_candidates!(BitIndex8);
+impl_candidates!(BitIndex16);
+impl_candidates!(BitIndex32);
+impl_candidates!(BitIndex64);
+impl_candidates!(BitIndex128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(digits: &[u8]) -> BitIndex16 {
+        let mut bi = BitIndex16::empty(9).unwrap();
+        bi.set_bits(digits);
+        bi
+    }
+
+    #[test]
+    fn only_and_eliminate() {
+        let mut bi = cell(&[1, 4, 7]);
+        assert_eq!(None, bi.only_candidate());
+        assert!(bi.eliminate(&cell(&[1, 7, 8])));
+        assert_eq!(Some(4), bi.only_candidate());
+        assert!(!bi.eliminate(&cell(&[0, 8])));
+        let all = bi;
+        assert!(bi.eliminate(&all));
+        assert_eq!(None, bi.only_candidate());
+    }
+
+    #[test]
+    fn hidden_single() {
+        let bi = cell(&[2, 5, 6]);
+        assert_eq!(Some(6), bi.hidden_single(&[cell(&[2, 3]), cell(&[5, 8])]));
+        assert_eq!(None, bi.hidden_single(&[cell(&[2])]));
+        assert_eq!(None, bi.hidden_single(&[bi]));
+    }
+
+    #[test]
+    fn naked_pairs() {
+        let cells = [
+            cell(&[1, 2]),
+            cell(&[1, 2, 3]),
+            cell(&[4, 5]),
+            cell(&[1, 2]),
+            cell(&[4, 5]),
+            cell(&[6]),
+        ];
+        assert_eq!(vec![(0, 3), (2, 4)], BitIndex16::naked_pairs(&cells));
+        assert!(BitIndex16::naked_pairs(&cells[..3]).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::BitIndex64;
+
+/// A read-only view of a slice of `BitIndex64` as one logical index.
+///
+/// Word `w` covers indices `64 * w..64 * (w + 1)`, whatever its `nb_bits`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ChainedBits<'a> {
+    words: &'a [BitIndex64],
+}
+
+impl<'a> ChainedBits<'a> {
+    pub fn new(words: &'a [BitIndex64]) -> Self {
+        Self { words }
+    }
+
+    /// The number of addressable bits.
+    pub fn nb_bits(&self) -> usize {
+        self.words.len() * 64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| word.is_empty())
+    }
+
+    pub fn nb_elements(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.bits.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn contains(&self, idx: usize) -> bool {
+        self.check_input(idx);
+        self.words[idx / 64].bits >> (idx % 64) & 1 == 1
+    }
+
+    pub fn smallest(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .find(|(_, word)| !word.is_empty())
+            .map(|(w, word)| 64 * w + word.bits.trailing_zeros() as usize)
+    }
+
+    pub fn largest(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, word)| !word.is_empty())
+            .map(|(w, word)| 64 * w + 63 - word.bits.leading_zeros() as usize)
+    }
+
+    /// The number of set bits strictly below `idx`, for `idx` upto `nb_bits()`.
+    pub fn rank(&self, idx: usize) -> usize {
+        if idx > self.nb_bits() {
+            panic!("This ChainedBits can only rank upto {}", self.nb_bits());
+        }
+        let (w, bit) = (idx / 64, idx % 64);
+        let below: usize = self.words[..w]
+            .iter()
+            .map(|word| word.bits.count_ones() as usize)
+            .sum();
+        match self.words.get(w) {
+            Some(word) if bit > 0 => below + (word.bits << (64 - bit)).count_ones() as usize,
+            _ => below,
+        }
+    }
+
+    /// The `n`th set bit in increasing order, counting from zero.
+    pub fn select(&self, mut n: usize) -> Option<usize> {
+        for (w, word) in self.words.iter().enumerate() {
+            let nb_ones = word.bits.count_ones() as usize;
+            if n < nb_ones {
+                let mut bits = word.bits;
+                for _ in 0..n {
+                    bits &= bits - 1;
+                }
+                return Some(64 * w + bits.trailing_zeros() as usize);
+            }
+            n -= nb_ones;
+        }
+        None
+    }
+
+    /// The set bits in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + 'a {
+        self.words.iter().enumerate().flat_map(|(w, word)| {
+            let mut bits = word.bits;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(64 * w + bit)
+            })
+        })
+    }
+
+    #[inline]
+    fn check_input(&self, idx: usize) {
+        if idx >= self.nb_bits() {
+            panic!(
+                "This ChainedBits can only handle inputs upto {}",
+                self.nb_bits()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words() -> Vec<BitIndex64> {
+        let mut words = vec![BitIndex64::empty(64).unwrap(); 3];
+        words[0].set_bits(&[3, 63]);
+        words[2].set_bits(&[0, 10]);
+        words
+    }
+
+    #[test]
+    fn queries() {
+        let words = words();
+        let chained = ChainedBits::new(&words);
+        assert_eq!(192, chained.nb_bits());
+        assert_eq!(4, chained.nb_elements());
+        assert!(chained.contains(63));
+        assert!(chained.contains(138));
+        assert!(!chained.contains(64));
+        assert_eq!(Some(3), chained.smallest());
+        assert_eq!(Some(138), chained.largest());
+        assert_eq!(vec![3, 63, 128, 138], chained.iter().collect::<Vec<_>>());
+        assert!(ChainedBits::new(&[]).is_empty());
+        assert_eq!(None, ChainedBits::new(&[]).largest());
+    }
+
+    #[test]
+    fn rank_select() {
+        let words = words();
+        let chained = ChainedBits::new(&words);
+        assert_eq!(0, chained.rank(3));
+        assert_eq!(1, chained.rank(4));
+        assert_eq!(2, chained.rank(64));
+        assert_eq!(2, chained.rank(128));
+        assert_eq!(4, chained.rank(192));
+        assert_eq!(Some(63), chained.select(1));
+        assert_eq!(Some(138), chained.select(3));
+        assert_eq!(None, chained.select(4));
+        assert!((0..4).all(|n| chained.rank(chained.select(n).unwrap()) == n));
+    }
+
+    #[test]
+    #[should_panic]
+    fn contains_panic() {
+        let words = words();
+        ChainedBits::new(&words).contains(192);
+    }
+}
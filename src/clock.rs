@@ -0,0 +1,125 @@
+use crate::BitIndex;
+
+/// Second-chance (clock) page replacement over `nb_bits` frames.
+///
+/// A set bit is a frame referenced since the hand last passed it. `evict` sweeps from the hand,
+/// clearing the reference bits it passes, and picks the first frame found unreferenced.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ClockBits<B> {
+    referenced: B,
+    hand: u8,
+}
+
+impl<B: BitIndex> ClockBits<B> {
+    /// `nb_frames` unreferenced frames, with the hand on frame 0.
+    pub fn new(nb_frames: u8) -> Result<Self, String> {
+        if nb_frames == 0 {
+            return Err("A ClockBits needs at least one frame".to_string());
+        }
+        B::empty(nb_frames).map(|referenced| Self {
+            referenced,
+            hand: 0,
+        })
+    }
+
+    pub fn nb_frames(&self) -> u8 {
+        self.referenced.nb_bits()
+    }
+
+    /// The frame the next sweep starts from.
+    pub fn hand(&self) -> u8 {
+        self.hand
+    }
+
+    pub fn referenced(&self) -> &B {
+        &self.referenced
+    }
+
+    pub fn is_referenced(&self, frame: u8) -> bool {
+        self.referenced.contains(frame)
+    }
+
+    /// Marks `frame` as referenced.
+    pub fn touch(&mut self, frame: u8) {
+        self.referenced.set_bit(frame);
+    }
+
+    /// Picks the frame to evict and moves the hand past it.
+    /// If every frame is referenced, all get their second chance and the hand's frame is picked.
+    pub fn evict(&mut self) -> u8 {
+        let nb_frames = self.nb_frames();
+        let mut unreferenced = self.referenced;
+        unreferenced.restore();
+        let unreferenced = unreferenced.difference(&self.referenced);
+        let victim = match self.hand {
+            0 => unreferenced.smallest(),
+            hand => unreferenced.next_set_bit_wrapping(hand - 1),
+        };
+        match victim {
+            Some(victim) if victim >= self.hand => {
+                self.referenced.unset_from_ranges(Some(self.hand..victim));
+            }
+            Some(victim) => {
+                self.referenced
+                    .unset_from_ranges([self.hand..nb_frames, 0..victim]);
+            }
+            None => self.referenced.clear(),
+        }
+        let victim = victim.unwrap_or(self.hand);
+        self.hand = if victim + 1 == nb_frames {
+            0
+        } else {
+            victim + 1
+        };
+        victim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitIndex128, BitIndex8};
+
+    #[test]
+    fn second_chance() {
+        let mut clock = ClockBits::<BitIndex8>::new(4).unwrap();
+        assert_eq!(0, clock.evict());
+        assert_eq!(1, clock.hand());
+
+        clock.touch(1);
+        clock.touch(2);
+        assert_eq!(3, clock.evict());
+        assert!(!clock.is_referenced(1) && !clock.is_referenced(2));
+        assert_eq!(0, clock.hand());
+
+        // sweep from 0: 0 and 1 are spared, 2 goes
+        clock.touch(0);
+        clock.touch(1);
+        clock.touch(3);
+        assert_eq!(2, clock.evict());
+        assert_eq!(0b1000, clock.referenced().unwrap());
+
+        // wrapping from 3 to 1 spares 3 and 0
+        clock.touch(0);
+        assert_eq!(1, clock.evict());
+        assert!(clock.referenced().is_empty());
+        assert_eq!(2, clock.evict());
+        assert_eq!(3, clock.evict());
+        assert_eq!(0, clock.evict());
+    }
+
+    #[test]
+    fn all_referenced() {
+        let mut clock = ClockBits::<BitIndex128>::new(128).unwrap();
+        for frame in 0..128 {
+            clock.touch(frame);
+        }
+        assert_eq!(0, clock.evict());
+        assert!(clock.referenced().is_empty());
+        clock.touch(127);
+        clock.touch(1);
+        assert_eq!(2, clock.evict());
+        assert!(clock.is_referenced(127));
+        assert!(ClockBits::<BitIndex8>::new(0).is_err());
+    }
+}
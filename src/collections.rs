@@ -0,0 +1,94 @@
+//! Conversions between the indexes and the std set types.
+
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+use std::collections::{BTreeSet, HashSet};
+use std::convert::TryFrom;
+
+macro_rules! impl_collections {
+    ($bit_index_name:ident) => {
+        impl $bit_index_name {
+            pub fn to_btreeset(&self) -> BTreeSet<u8> {
+                self.elements().collect()
+            }
+
+            pub fn to_hashset(&self) -> HashSet<u8> {
+                self.elements().collect()
+            }
+
+            fn elements(&self) -> impl Iterator<Item = u8> {
+                let mut remaining = *self;
+                std::iter::from_fn(move || remaining.pop_smallest())
+            }
+
+            fn from_elements<'a, I: IntoIterator<Item = &'a u8>>(
+                elements: I,
+            ) -> Result<Self, String> {
+                let mut bi = Self::empty(Self::SIZE)?;
+                for &bit_nb in elements {
+                    if bit_nb >= Self::SIZE {
+                        return Err(format!(
+                            "This {} can only keep {} bits, {} does not fit",
+                            stringify!($bit_index_name),
+                            Self::SIZE,
+                            bit_nb
+                        ));
+                    }
+                    bi.set_bit(bit_nb);
+                }
+                Ok(bi)
+            }
+        }
+
+        /// Takes the full width as `nb_bits`; fails if an element does not fit.
+        impl TryFrom<&BTreeSet<u8>> for $bit_index_name {
+            type Error = String;
+
+            fn try_from(set: &BTreeSet<u8>) -> Result<Self, Self::Error> {
+                Self::from_elements(set)
+            }
+        }
+
+        /// Takes the full width as `nb_bits`; fails if an element does not fit.
+        impl TryFrom<&HashSet<u8>> for $bit_index_name {
+            type Error = String;
+
+            fn try_from(set: &HashSet<u8>) -> Result<Self, Self::Error> {
+                Self::from_elements(set)
+            }
+        }
+    };
+}
+
+impl_collections!(BitIndex8);
+impl_collections!(BitIndex16);
+impl_collections!(BitIndex32);
+impl_collections!(BitIndex64);
+impl_collections!(BitIndex128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn btreeset() {
+        let set: BTreeSet<u8> = [1, 4, 7].iter().copied().collect();
+        let bi = BitIndex8::try_from(&set).unwrap();
+        assert_eq!(0b10010010, bi.unwrap());
+        assert_eq!(8, bi.nb_bits());
+        assert_eq!(set, bi.to_btreeset());
+
+        let too_large: BTreeSet<u8> = [1, 8].iter().copied().collect();
+        assert!(BitIndex8::try_from(&too_large).is_err());
+        assert!(BitIndex16::try_from(&too_large).is_ok());
+    }
+
+    #[test]
+    fn hashset() {
+        let set: HashSet<u8> = [0, 100, 127].iter().copied().collect();
+        let bi = BitIndex128::try_from(&set).unwrap();
+        assert_eq!(3, bi.nb_elements());
+        assert_eq!(set, bi.to_hashset());
+        assert!(BitIndex64::try_from(&set).is_err());
+        assert!(BitIndex32::try_from(&HashSet::new()).unwrap().is_empty());
+    }
+}
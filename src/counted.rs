@@ -0,0 +1,128 @@
+use crate::BitIndex;
+
+/// Wraps a `BitIndex` and keeps its number of elements up to date on every mutation,
+/// so `nb_elements` is a field read instead of a population count.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CountedBitIndex<B> {
+    inner: B,
+    nb_elements: u8,
+}
+
+impl<B: BitIndex> CountedBitIndex<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            nb_elements: inner.nb_elements(),
+            inner,
+        }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    #[inline]
+    pub fn nb_elements(&self) -> u8 {
+        self.nb_elements
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nb_elements == 0
+    }
+
+    pub fn nb_bits(&self) -> u8 {
+        self.inner.nb_bits()
+    }
+
+    pub fn contains(&self, bit_nb: u8) -> bool {
+        self.inner.contains(bit_nb)
+    }
+
+    pub fn set_bit(&mut self, bit_nb: u8) {
+        if !self.inner.contains(bit_nb) {
+            self.inner.set_bit(bit_nb);
+            self.nb_elements += 1;
+        }
+    }
+
+    pub fn unset_bit(&mut self, bit_nb: u8) {
+        if self.inner.contains(bit_nb) {
+            self.inner.unset_bit(bit_nb);
+            self.nb_elements -= 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+        self.nb_elements = 0;
+    }
+
+    pub fn restore(&mut self) {
+        self.inner.restore();
+        self.nb_elements = self.inner.nb_bits();
+    }
+
+    pub fn smallest(&self) -> Option<u8> {
+        self.inner.smallest()
+    }
+
+    pub fn largest(&self) -> Option<u8> {
+        self.inner.largest()
+    }
+
+    pub fn pop_smallest(&mut self) -> Option<u8> {
+        let res = self.inner.pop_smallest();
+        if res.is_some() {
+            self.nb_elements -= 1;
+        }
+        res
+    }
+
+    pub fn pop_largest(&mut self) -> Option<u8> {
+        let res = self.inner.pop_largest();
+        if res.is_some() {
+            self.nb_elements -= 1;
+        }
+        res
+    }
+}
+
+impl<B: BitIndex> From<B> for CountedBitIndex<B> {
+    fn from(inner: B) -> Self {
+        Self::new(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitIndex64;
+
+    #[test]
+    fn count_follows_mutations() {
+        let mut counted = CountedBitIndex::new(BitIndex64::new(40).unwrap());
+        assert_eq!(40, counted.nb_elements());
+        counted.unset_bit(3);
+        counted.unset_bit(3);
+        assert_eq!(39, counted.nb_elements());
+        counted.set_bit(3);
+        counted.set_bit(3);
+        assert_eq!(40, counted.nb_elements());
+        assert_eq!(Some(39), counted.pop_largest());
+        assert_eq!(Some(0), counted.pop_smallest());
+        assert_eq!(38, counted.nb_elements());
+        assert_eq!(counted.inner().nb_elements(), counted.nb_elements());
+
+        counted.clear();
+        assert!(counted.is_empty());
+        assert_eq!(None, counted.pop_smallest());
+        assert_eq!(0, counted.nb_elements());
+        counted.restore();
+        assert_eq!(40, counted.nb_elements());
+        assert_eq!(40, counted.into_inner().nb_elements());
+    }
+}
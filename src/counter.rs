@@ -0,0 +1,139 @@
+use crate::{BitIndex16, BitIndex32, BitIndex8};
+
+macro_rules! impl_bit_counter {
+    ($bit_counter_name:ident, $backing_type:ty, $bit_index_name:ident, $bit_index_type:ty) => {
+        /// A multiset of slots: a 4-bit counter per slot, packed in a single integer.
+        /// Little-endian and zero-indexed, like the `BitIndex` of its nonzero slots.
+        #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+        pub struct $bit_counter_name {
+            counters: $backing_type,
+            nb_slots: u8,
+        }
+
+        impl $bit_counter_name {
+            pub const MAX_COUNT: u8 = 0xF;
+            const SIZE: u8 = (std::mem::size_of::<$backing_type>() * 2) as u8;
+
+            pub fn new(nb_slots: u8) -> Result<Self, String> {
+                if nb_slots > Self::SIZE {
+                    Err(format!(
+                        "This {} can only keep {} slots, not {}",
+                        stringify!($bit_counter_name),
+                        Self::SIZE,
+                        nb_slots
+                    ))
+                } else {
+                    Ok(Self {
+                        counters: 0,
+                        nb_slots,
+                    })
+                }
+            }
+
+            pub fn nb_slots(&self) -> u8 {
+                self.nb_slots
+            }
+
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.counters == 0
+            }
+
+            #[inline]
+            pub fn clear(&mut self) {
+                self.counters = 0;
+            }
+
+            pub fn count(&self, idx: u8) -> u8 {
+                self.check_input(idx);
+                (self.counters >> (4 * idx as u32) & 0xF) as u8
+            }
+
+            /// Returns the new count, or `None` if the counter is already at `MAX_COUNT`.
+            pub fn increment(&mut self, idx: u8) -> Option<u8> {
+                let count = self.count(idx);
+                if count == Self::MAX_COUNT {
+                    return None;
+                }
+                self.counters += 1 << (4 * idx as u32);
+                Some(count + 1)
+            }
+
+            /// Returns the new count, or `None` if the counter is already zero.
+            pub fn decrement(&mut self, idx: u8) -> Option<u8> {
+                let count = self.count(idx);
+                if count == 0 {
+                    return None;
+                }
+                self.counters -= 1 << (4 * idx as u32);
+                Some(count - 1)
+            }
+
+            pub fn nonzero_mask(&self) -> $bit_index_name {
+                let bits = (0..self.nb_slots)
+                    .filter(|&idx| self.count(idx) != 0)
+                    .fold(0, |bits: $bit_index_type, idx| bits | 1 << idx);
+                $bit_index_name {
+                    bits,
+                    nb_bits: self.nb_slots,
+                }
+            }
+
+            #[inline]
+            fn check_input(&self, idx: u8) {
+                if idx >= self.nb_slots {
+                    panic!(
+                        "This {} can only handle inputs upto {}",
+                        stringify!($bit_counter_name),
+                        self.nb_slots
+                    )
+                }
+            }
+        }
+    };
+}
+
+impl_bit_counter!(BitCounter8, u32, BitIndex8, u8);
+impl_bit_counter!(BitCounter16, u64, BitIndex16, u16);
+impl_bit_counter!(BitCounter32, u128, BitIndex32, u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn increment_decrement() {
+        let mut counter = BitCounter16::new(10).unwrap();
+        assert!(counter.is_empty());
+        assert_eq!(Some(1), counter.increment(3));
+        assert_eq!(Some(2), counter.increment(3));
+        assert_eq!(Some(1), counter.increment(9));
+        assert_eq!(2, counter.count(3));
+        assert_eq!(0b1000001000, counter.nonzero_mask().unwrap());
+        assert_eq!(10, counter.nonzero_mask().nb_bits());
+
+        assert_eq!(Some(1), counter.decrement(3));
+        assert_eq!(Some(0), counter.decrement(3));
+        assert_eq!(None, counter.decrement(3));
+        assert_eq!(0b1000000000, counter.nonzero_mask().unwrap());
+    }
+
+    #[test]
+    fn saturation() {
+        let mut counter = BitCounter32::new(32).unwrap();
+        for _ in 0..BitCounter32::MAX_COUNT {
+            counter.increment(31);
+        }
+        assert_eq!(None, counter.increment(31));
+        assert_eq!(15, counter.count(31));
+        assert_eq!(0, counter.count(30));
+        assert_eq!(Some(31), counter.nonzero_mask().largest());
+        assert!(BitCounter8::new(9).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn count_panic() {
+        BitCounter8::new(4).unwrap().count(4);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::BitIndex128;
+
+/// Occupancy of an `X` × `Y` × `Z` voxel grid, stored in a `BitIndex128`.
+///
+/// Voxel `(x, y, z)` is bit `x + X * (y + Y * z)`, so the grid holds at most 128 voxels.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BitCube<const X: u8, const Y: u8, const Z: u8> {
+    index: BitIndex128,
+}
+
+impl<const X: u8, const Y: u8, const Z: u8> BitCube<X, Y, Z> {
+    const NB_VOXELS: usize = X as usize * Y as usize * Z as usize;
+
+    /// An empty cube. Every dimension must be at least 1.
+    pub fn new() -> Result<Self, String> {
+        if X == 0 || Y == 0 || Z == 0 {
+            return Err(format!(
+                "A BitCube needs a voxel in every dimension, not {}x{}x{}",
+                X, Y, Z
+            ));
+        }
+        if Self::NB_VOXELS > 128 {
+            return Err(format!(
+                "A BitCube can only keep 128 voxels, not {}x{}x{}",
+                X, Y, Z
+            ));
+        }
+        BitIndex128::empty(Self::NB_VOXELS as u8).map(|index| Self { index })
+    }
+
+    pub fn index(&self) -> &BitIndex128 {
+        &self.index
+    }
+
+    pub fn nb_elements(&self) -> u8 {
+        self.index.nb_elements()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn contains(&self, x: u8, y: u8, z: u8) -> bool {
+        self.index.contains(Self::bit_nb(x, y, z))
+    }
+
+    pub fn set(&mut self, x: u8, y: u8, z: u8) {
+        self.index.set_bit(Self::bit_nb(x, y, z));
+    }
+
+    pub fn unset(&mut self, x: u8, y: u8, z: u8) {
+        self.index.unset_bit(Self::bit_nb(x, y, z));
+    }
+
+    /// The occupied voxels as `(x, y, z)`, in bit order.
+    pub fn voxels(&self) -> impl Iterator<Item = (u8, u8, u8)> {
+        let mut remaining = self.index;
+        std::iter::from_fn(move || remaining.pop_smallest()).map(Self::coords)
+    }
+
+    /// Moves every voxel `delta` along the x axis; voxels leaving the cube are dropped.
+    pub fn shift_x(&mut self, delta: i8) {
+        self.shift(delta, 1, X);
+    }
+
+    /// Moves every voxel `delta` along the y axis; voxels leaving the cube are dropped.
+    pub fn shift_y(&mut self, delta: i8) {
+        self.shift(delta, X as usize, Y);
+    }
+
+    /// Moves every voxel `delta` along the z axis; voxels leaving the cube are dropped.
+    pub fn shift_z(&mut self, delta: i8) {
+        self.shift(delta, X as usize * Y as usize, Z);
+    }
+
+    /// The plane at `x`, as a `Y * Z` bit index with `(y, z)` at bit `y + Y * z`.
+    pub fn plane_x(&self, x: u8) -> BitIndex128 {
+        self.plane(Y * Z, |bit_nb| (x, bit_nb % Y, bit_nb / Y))
+    }
+
+    /// The plane at `y`, as a `X * Z` bit index with `(x, z)` at bit `x + X * z`.
+    pub fn plane_y(&self, y: u8) -> BitIndex128 {
+        self.plane(X * Z, |bit_nb| (bit_nb % X, y, bit_nb / X))
+    }
+
+    /// The plane at `z`, as a `X * Y` bit index with `(x, y)` at bit `x + X * y`.
+    pub fn plane_z(&self, z: u8) -> BitIndex128 {
+        self.plane(X * Y, |bit_nb| (bit_nb % X, bit_nb / X, z))
+    }
+
+    // Moves the voxels `delta` steps along the axis whose coordinate has bit stride `stride`
+    // and `len` values: the voxels that stay inside are masked, then shifted all at once.
+    fn shift(&mut self, delta: i8, stride: usize, len: u8) {
+        let steps = delta.unsigned_abs();
+        if steps >= len {
+            self.index.clear();
+            return;
+        }
+        let kept = if delta < 0 {
+            Self::band(stride, len, steps, len)
+        } else {
+            Self::band(stride, len, 0, len - steps)
+        };
+        let bits = self.index.bits & kept;
+        let amount = stride * steps as usize;
+        self.index.bits = if delta < 0 {
+            bits >> amount
+        } else {
+            bits << amount
+        };
+    }
+
+    // The voxels whose coordinate along the axis of `stride` and `len` is in `from..to`.
+    fn band(stride: usize, len: u8, from: u8, to: u8) -> u128 {
+        let slab = BitIndex128::init((stride * to as usize) as u8)
+            ^ BitIndex128::init((stride * from as usize) as u8);
+        (0..Self::NB_VOXELS)
+            .step_by(stride * len as usize)
+            .fold(0, |mask, start| mask | slab << start)
+    }
+
+    fn plane(&self, nb_bits: u8, coords: impl Fn(u8) -> (u8, u8, u8)) -> BitIndex128 {
+        let mut plane = BitIndex128::empty(nb_bits).unwrap();
+        for bit_nb in 0..nb_bits {
+            let (x, y, z) = coords(bit_nb);
+            if self.contains(x, y, z) {
+                plane.set_bit(bit_nb);
+            }
+        }
+        plane
+    }
+
+    fn bit_nb(x: u8, y: u8, z: u8) -> u8 {
+        if x >= X || y >= Y || z >= Z {
+            panic!(
+                "This BitCube can only handle inputs upto ({}, {}, {})",
+                X, Y, Z
+            );
+        }
+        x + X * (y + Y * z)
+    }
+
+    fn coords(bit_nb: u8) -> (u8, u8, u8) {
+        (bit_nb % X, bit_nb / X % Y, bit_nb / X / Y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addressing() {
+        let mut cube = BitCube::<4, 3, 2>::new().unwrap();
+        cube.set(3, 2, 1);
+        cube.set(1, 0, 0);
+        assert!(cube.contains(3, 2, 1));
+        assert_eq!(Some(23), cube.index().largest());
+        assert_eq!(
+            vec![(1, 0, 0), (3, 2, 1)],
+            cube.voxels().collect::<Vec<_>>()
+        );
+        cube.unset(1, 0, 0);
+        assert_eq!(1, cube.nb_elements());
+        assert!(BitCube::<8, 8, 3>::new().is_err());
+        assert!(BitCube::<4, 4, 8>::new().is_ok());
+        assert!(BitCube::<0, 20, 20>::new().is_err());
+        assert!(BitCube::<4, 0, 1>::new().is_err());
+    }
+
+    #[test]
+    fn shifts() {
+        let mut cube = BitCube::<3, 3, 3>::new().unwrap();
+        cube.set(0, 0, 0);
+        cube.set(2, 1, 1);
+        cube.shift_x(1);
+        assert_eq!(vec![(1, 0, 0)], cube.voxels().collect::<Vec<_>>());
+        cube.shift_z(2);
+        cube.shift_y(-1);
+        assert!(cube.is_empty());
+
+        cube.set(1, 1, 1);
+        cube.shift_y(-1);
+        cube.shift_z(1);
+        assert!(cube.contains(1, 0, 2));
+        cube.shift_x(-3);
+        assert!(cube.is_empty());
+
+        // against moving the voxels one by one
+        let mut cube = BitCube::<4, 2, 3>::new().unwrap();
+        for voxel in (0..24).filter(|voxel| voxel * 5 % 7 < 4) {
+            cube.set(voxel % 4, voxel / 4 % 2, voxel / 8);
+        }
+        for axis in 0..3 {
+            for delta in -4..=4 {
+                let mut shifted = cube;
+                match axis {
+                    0 => shifted.shift_x(delta),
+                    1 => shifted.shift_y(delta),
+                    _ => shifted.shift_z(delta),
+                }
+                let mut expected = BitCube::<4, 2, 3>::new().unwrap();
+                for (x, y, z) in cube.voxels() {
+                    let mut moved = [x as i8, y as i8, z as i8];
+                    moved[axis] += delta;
+                    if (0..4).contains(&moved[0])
+                        && (0..2).contains(&moved[1])
+                        && (0..3).contains(&moved[2])
+                    {
+                        expected.set(moved[0] as u8, moved[1] as u8, moved[2] as u8);
+                    }
+                }
+                assert_eq!(expected, shifted, "axis {} delta {}", axis, delta);
+            }
+        }
+    }
+
+    #[test]
+    fn planes() {
+        let mut cube = BitCube::<2, 3, 4>::new().unwrap();
+        cube.set(1, 2, 3);
+        cube.set(0, 2, 0);
+        let plane = cube.plane_z(3);
+        assert_eq!((6, 0b100000), (plane.nb_bits(), plane.unwrap()));
+        let plane = cube.plane_y(2);
+        assert_eq!((8, 0b10000001), (plane.nb_bits(), plane.unwrap()));
+        let plane = cube.plane_x(0);
+        assert_eq!((12, 0b100), (plane.nb_bits(), plane.unwrap()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        BitCube::<2, 2, 2>::new().unwrap().set(0, 2, 0);
+    }
+}
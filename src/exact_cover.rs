@@ -0,0 +1,155 @@
+use crate::BitIndex64;
+
+/// All ways to pick rows that cover every column set in any row exactly once.
+///
+/// Each solution lists the indexes of its rows in increasing order. The search is depth-first
+/// (Knuth's Algorithm X on masks): it always branches on the uncovered column with the fewest
+/// rows left, and skips rows overlapping a column that is already covered.
+/// Solutions are produced lazily, so `.next()` finds a single one without exploring the rest.
+pub fn solve_exact_cover(rows: &[BitIndex64]) -> impl Iterator<Item = Vec<usize>> + '_ {
+    let universe = rows.iter().fold(0, |acc, row| acc | row.bits);
+    let mut search = ExactCover {
+        rows,
+        universe,
+        stack: Vec::new(),
+        chosen: Vec::new(),
+    };
+    let trivial = universe == 0;
+    if !trivial {
+        search.stack.extend(search.frame(0));
+    }
+    std::iter::once(Vec::new())
+        .filter(move |_| trivial)
+        .chain(search)
+}
+
+struct ExactCover<'a> {
+    rows: &'a [BitIndex64],
+    universe: u64,
+    stack: Vec<Frame>,
+    chosen: Vec<usize>,
+}
+
+// The rows that can cover one column, given the columns covered so far.
+struct Frame {
+    covered: u64,
+    candidates: Vec<usize>,
+    next: usize,
+}
+
+impl ExactCover<'_> {
+    fn candidates(&self, covered: u64, column: u32) -> impl Iterator<Item = usize> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(move |(_, row)| row.bits >> column & 1 == 1 && row.bits & covered == 0)
+            .map(|(i, _)| i)
+    }
+
+    // Branches on the uncovered column with the fewest candidates; `None` if one has none.
+    fn frame(&self, covered: u64) -> Option<Frame> {
+        let mut uncovered = self.universe & !covered;
+        let mut best: Option<(usize, u32)> = None;
+        while uncovered != 0 {
+            let column = uncovered.trailing_zeros();
+            uncovered &= uncovered - 1;
+            let count = self.candidates(covered, column).count();
+            if count == 0 {
+                return None;
+            }
+            match best {
+                Some((best_count, _)) if best_count <= count => {}
+                _ => best = Some((count, column)),
+            }
+        }
+        best.map(|(_, column)| Frame {
+            covered,
+            candidates: self.candidates(covered, column).collect(),
+            next: 0,
+        })
+    }
+}
+
+impl Iterator for ExactCover<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        loop {
+            let depth = self.stack.len().checked_sub(1)?;
+            let frame = &mut self.stack[depth];
+            let row = match frame.candidates.get(frame.next) {
+                Some(&row) => row,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+            frame.next += 1;
+            let covered = frame.covered | self.rows[row].bits;
+            self.chosen.truncate(depth);
+            self.chosen.push(row);
+
+            if covered == self.universe {
+                let mut solution = self.chosen.clone();
+                solution.sort_unstable();
+                return Some(solution);
+            }
+            if let Some(frame) = self.frame(covered) {
+                self.stack.push(frame);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(sets: &[&[u8]]) -> Vec<BitIndex64> {
+        sets.iter()
+            .map(|set| {
+                let mut bi = BitIndex64::empty(7).unwrap();
+                bi.set_bits(set);
+                bi
+            })
+            .collect()
+    }
+
+    #[test]
+    fn knuth_example() {
+        let rows = rows(&[
+            &[0, 3, 6],
+            &[0, 3],
+            &[3, 4, 6],
+            &[2, 4, 5],
+            &[1, 2, 5, 6],
+            &[1, 6],
+        ]);
+        let solutions: Vec<_> = solve_exact_cover(&rows).collect();
+        assert_eq!(vec![vec![1, 3, 5]], solutions);
+    }
+
+    #[test]
+    fn several_or_none() {
+        let rows = rows(&[&[0], &[1, 2], &[0, 1], &[2], &[]]);
+        let mut solutions: Vec<_> = solve_exact_cover(&rows).collect();
+        solutions.sort();
+        assert_eq!(vec![vec![0, 1], vec![2, 3]], solutions);
+
+        assert_eq!(None, solve_exact_cover(&rows[1..3]).next());
+        assert_eq!(
+            vec![vec![0]],
+            solve_exact_cover(&rows[..1]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn empty_universe() {
+        assert_eq!(
+            vec![Vec::<usize>::new()],
+            solve_exact_cover(&[]).collect::<Vec<_>>()
+        );
+        let empty = rows(&[&[]]);
+        assert_eq!(1, solve_exact_cover(&empty).count());
+    }
+}
@@ -0,0 +1,240 @@
+//! Operations over collections of masks.
+
+use crate::{BitIndex, BitIndex64};
+use std::collections::HashSet;
+
+/// Removes every mask that is a subset of another mask in `masks`, keeping the order of the rest.
+/// Of several equal masks, the first one is kept.
+///
+/// Masks are visited from the most to the least elements, so each one is only compared with the
+/// kept masks that have more elements; kept masks with as many elements can only be equal to it.
+pub fn remove_subsumed<B: BitIndex>(masks: &mut Vec<B>) {
+    let mut order: Vec<usize> = (0..masks.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(masks[i].nb_elements()));
+
+    let mut keep = vec![false; masks.len()];
+    let mut larger: Vec<u128> = Vec::new();
+    let mut same_size: HashSet<u128> = HashSet::new();
+    let mut size = None;
+    for i in order {
+        let nb_elements = masks[i].nb_elements();
+        if size != Some(nb_elements) {
+            larger.extend(same_size.drain());
+            size = Some(nb_elements);
+        }
+        let bits = masks[i].to_u128();
+        if !same_size.contains(&bits) && larger.iter().all(|&kept| bits & !kept != 0) {
+            same_size.insert(bits);
+            keep[i] = true;
+        }
+    }
+
+    let mut keep = keep.into_iter();
+    masks.retain(|_| keep.next().unwrap());
+}
+
+/// Indexes of `candidates` covering `universe`, picked greedily: each step takes the candidate
+/// covering the most elements not covered yet, the first one on ties.
+///
+/// Stops when nothing more can be covered, so elements no candidate contains stay uncovered.
+pub fn greedy_cover<B: BitIndex>(universe: B, candidates: &[B]) -> Vec<usize> {
+    let bits: Vec<u128> = candidates.iter().map(|c| c.to_u128()).collect();
+    let mut uncovered = universe.to_u128();
+    let mut cover = Vec::new();
+    while uncovered != 0 {
+        let best = bits
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| ((c & uncovered).count_ones(), i))
+            .max_by_key(|&(gain, i)| (gain, std::cmp::Reverse(i)));
+        match best {
+            Some((gain, i)) if gain > 0 => {
+                uncovered &= !bits[i];
+                cover.push(i);
+            }
+            _ => break,
+        }
+    }
+    cover
+}
+
+/// The inverted index of `masks`: for every bit below `universe`, the set of masks containing it.
+///
+/// Each set is a list of words where mask `i` is bit `i % 64` of word `i / 64`, to be read
+/// through `ChainedBits`. Bits at or above `universe` are ignored.
+pub fn invert_collection<B: BitIndex>(masks: &[B], universe: u8) -> Vec<Vec<BitIndex64>> {
+    let nb_words = masks.len().div_ceil(64);
+    let mut postings = vec![vec![BitIndex64::empty(64).unwrap(); nb_words]; universe as usize];
+    let in_universe = if universe >= 128 {
+        u128::MAX
+    } else {
+        (1 << universe) - 1
+    };
+    for (i, mask) in masks.iter().enumerate() {
+        let mut bits = mask.to_u128() & in_universe;
+        while bits != 0 {
+            postings[bits.trailing_zeros() as usize][i / 64].bits |= 1 << (i % 64);
+            bits &= bits - 1;
+        }
+    }
+    postings
+}
+
+// Side of the square tiles the pairwise loops work through, so both tiles stay in cache.
+const BLOCK: usize = 64;
+
+/// The number of shared elements of every pair of masks: entry `[i][j]` is the size of the
+/// intersection of `masks[i]` and `masks[j]`, and the diagonal holds the number of elements.
+pub fn intersection_matrix<B: BitIndex>(masks: &[B]) -> Vec<Vec<u8>> {
+    let mut matrix = vec![vec![0; masks.len()]; masks.len()];
+    for_each_pair(masks, |i, j, overlap| {
+        matrix[i][j] = overlap;
+        matrix[j][i] = overlap;
+    });
+    matrix
+}
+
+/// The pairs `(i, j, overlap)`, `i < j`, of masks sharing at least `min_overlap` elements,
+/// sorted by `i` then `j`.
+pub fn similar_pairs<B: BitIndex>(masks: &[B], min_overlap: u8) -> Vec<(usize, usize, u8)> {
+    let mut pairs = Vec::new();
+    for_each_pair(masks, |i, j, overlap| {
+        if i != j && overlap >= min_overlap {
+            pairs.push((i, j, overlap));
+        }
+    });
+    pairs.sort_unstable();
+    pairs
+}
+
+// Calls `f(i, j, overlap)` for every `i <= j`, tile by tile.
+fn for_each_pair<B: BitIndex, F: FnMut(usize, usize, u8)>(masks: &[B], mut f: F) {
+    let bits: Vec<u128> = masks.iter().map(|mask| mask.to_u128()).collect();
+    for i0 in (0..bits.len()).step_by(BLOCK) {
+        for j0 in (i0..bits.len()).step_by(BLOCK) {
+            for i in i0..(i0 + BLOCK).min(bits.len()) {
+                for j in j0.max(i)..(j0 + BLOCK).min(bits.len()) {
+                    f(i, j, (bits[i] & bits[j]).count_ones() as u8);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitIndex16, ChainedBits};
+
+    fn masks(sets: &[&[u8]]) -> Vec<BitIndex16> {
+        sets.iter()
+            .map(|set| {
+                let mut bi = BitIndex16::empty(16).unwrap();
+                bi.set_bits(set);
+                bi
+            })
+            .collect()
+    }
+
+    #[test]
+    fn subsumed() {
+        let mut family = masks(&[
+            &[1, 2],
+            &[1, 2, 3],
+            &[4],
+            &[2, 3],
+            &[1, 2],
+            &[5, 6],
+            &[],
+            &[4, 5, 6],
+        ]);
+        remove_subsumed(&mut family);
+        assert_eq!(masks(&[&[1, 2, 3], &[4, 5, 6]]), family);
+
+        let mut family = masks(&[&[3], &[1], &[3], &[2]]);
+        remove_subsumed(&mut family);
+        assert_eq!(masks(&[&[3], &[1], &[2]]), family);
+
+        let mut family: Vec<BitIndex16> = Vec::new();
+        remove_subsumed(&mut family);
+        assert!(family.is_empty());
+    }
+
+    #[test]
+    fn greedy() {
+        let universe = masks(&[&[0, 1, 2, 3, 4, 5, 6]])[0];
+        let candidates = masks(&[
+            &[0, 1],
+            &[2, 3, 4, 5],
+            &[0, 1, 2],
+            &[6],
+            &[3, 4, 5, 6],
+            &[5],
+        ]);
+        assert_eq!(vec![1, 0, 3], greedy_cover(universe, &candidates));
+
+        // element 9 cannot be covered
+        let universe = masks(&[&[0, 6, 9]])[0];
+        assert_eq!(vec![0, 3], greedy_cover(universe, &candidates));
+        assert!(greedy_cover(universe, &[]).is_empty());
+    }
+
+    #[test]
+    fn inverted() {
+        let mut family = masks(&[&[0, 2], &[2], &[5, 15]]);
+        family.extend(vec![masks(&[&[2]])[0]; 100]);
+        let postings = invert_collection(&family, 6);
+        assert_eq!(6, postings.len());
+        assert_eq!(2, postings[0].len());
+
+        let holders = |bit: usize| ChainedBits::new(&postings[bit]).iter().collect::<Vec<_>>();
+        assert_eq!(vec![0], holders(0));
+        assert!(holders(1).is_empty());
+        assert_eq!(102, holders(2).len());
+        assert_eq!(Some(&102), holders(2).last());
+        assert_eq!(vec![2], holders(5));
+        assert!(invert_collection::<BitIndex16>(&[], 3)
+            .iter()
+            .all(|words| words.is_empty()));
+    }
+
+    #[test]
+    fn overlaps() {
+        let family = masks(&[&[1, 2, 3], &[2, 3], &[4], &[1, 3, 4]]);
+        assert_eq!(
+            vec![
+                vec![3, 2, 0, 2],
+                vec![2, 2, 0, 1],
+                vec![0, 0, 1, 1],
+                vec![2, 1, 1, 3],
+            ],
+            intersection_matrix(&family)
+        );
+        assert_eq!(vec![(0, 1, 2), (0, 3, 2)], similar_pairs(&family, 2));
+        assert_eq!(4, similar_pairs(&family, 1).len());
+        assert!(intersection_matrix::<BitIndex16>(&[]).is_empty());
+    }
+
+    #[test]
+    fn overlaps_across_blocks() {
+        let family: Vec<_> = (0..200u32)
+            .map(|i| {
+                let mut bi = BitIndex16::empty(16).unwrap();
+                bi.add((i.wrapping_mul(2_654_435_761) >> 16) as u16);
+                bi
+            })
+            .collect();
+        let matrix = intersection_matrix(&family);
+        for (i, a) in family.iter().enumerate() {
+            for (j, b) in family.iter().enumerate() {
+                assert_eq!((a.unwrap() & b.unwrap()).count_ones() as u8, matrix[i][j]);
+            }
+        }
+        let pairs = similar_pairs(&family, 6);
+        let expected = (0..200)
+            .flat_map(|i| (i + 1..200).map(move |j| (i, j)))
+            .filter(|&(i, j)| matrix[i][j] >= 6)
+            .count();
+        assert_eq!(expected, pairs.len());
+    }
+}
@@ -0,0 +1,257 @@
+//! C bindings for the fixed-width indexes, enabled with the `ffi` feature.
+//!
+//! Every `BitIndexN` is `#[repr(C)]` (`bits` followed by `nb_bits`) and is passed by value.
+//! Functions never panic across the boundary: invalid input makes them return `false`
+//! and leave their output untouched. Pointer arguments may be null.
+//!
+//! The types and functions are generated by macros, so `cbindgen` has to run with
+//! `[parse.expand] crates = ["bit-index"]` and the `ffi` feature enabled to see them.
+
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+
+macro_rules! impl_ffi {
+    (
+        $bit_index_name:ident,
+        $bit_index_type:ty,
+        $new:ident,
+        $empty:ident,
+        $bits:ident,
+        $nb_bits:ident,
+        $nb_elements:ident,
+        $contains:ident,
+        $set_bit:ident,
+        $unset_bit:ident,
+        $smallest:ident,
+        $largest:ident,
+        $pop_smallest:ident,
+        $pop_largest:ident
+    ) => {
+        #[no_mangle]
+        pub extern "C" fn $new(nb_bits: u8, out: Option<&mut $bit_index_name>) -> bool {
+            match (<$bit_index_name>::new(nb_bits), out) {
+                (Ok(bi), Some(out)) => {
+                    *out = bi;
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $empty(nb_bits: u8, out: Option<&mut $bit_index_name>) -> bool {
+            match (<$bit_index_name>::empty(nb_bits), out) {
+                (Ok(bi), Some(out)) => {
+                    *out = bi;
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $bits(bi: $bit_index_name) -> $bit_index_type {
+            bi.bits
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $nb_bits(bi: $bit_index_name) -> u8 {
+            bi.nb_bits
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $nb_elements(bi: $bit_index_name) -> u8 {
+            bi.nb_elements()
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $contains(bi: $bit_index_name, bit_nb: u8) -> bool {
+            bit_nb < bi.nb_bits && bi.bits & (1 << bit_nb) != 0
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $set_bit(bi: Option<&mut $bit_index_name>, bit_nb: u8) -> bool {
+            match bi {
+                Some(bi) if bit_nb < bi.nb_bits => {
+                    bi.set_bit(bit_nb);
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $unset_bit(bi: Option<&mut $bit_index_name>, bit_nb: u8) -> bool {
+            match bi {
+                Some(bi) if bit_nb < bi.nb_bits => {
+                    bi.unset_bit(bit_nb);
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $smallest(bi: $bit_index_name, out: Option<&mut u8>) -> bool {
+            write_out(bi.smallest(), out)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $largest(bi: $bit_index_name, out: Option<&mut u8>) -> bool {
+            write_out(bi.largest(), out)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $pop_smallest(
+            bi: Option<&mut $bit_index_name>,
+            out: Option<&mut u8>,
+        ) -> bool {
+            match (bi, out) {
+                (Some(bi), Some(out)) => write_out(bi.pop_smallest(), Some(out)),
+                _ => false,
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn $pop_largest(
+            bi: Option<&mut $bit_index_name>,
+            out: Option<&mut u8>,
+        ) -> bool {
+            match (bi, out) {
+                (Some(bi), Some(out)) => write_out(bi.pop_largest(), Some(out)),
+                _ => false,
+            }
+        }
+    };
+}
+
+fn write_out(res: Option<u8>, out: Option<&mut u8>) -> bool {
+    match (res, out) {
+        (Some(bit_nb), Some(out)) => {
+            *out = bit_nb;
+            true
+        }
+        _ => false,
+    }
+}
+
+impl_ffi!(
+    BitIndex8,
+    u8,
+    bit_index8_new,
+    bit_index8_empty,
+    bit_index8_bits,
+    bit_index8_nb_bits,
+    bit_index8_nb_elements,
+    bit_index8_contains,
+    bit_index8_set_bit,
+    bit_index8_unset_bit,
+    bit_index8_smallest,
+    bit_index8_largest,
+    bit_index8_pop_smallest,
+    bit_index8_pop_largest
+);
+impl_ffi!(
+    BitIndex16,
+    u16,
+    bit_index16_new,
+    bit_index16_empty,
+    bit_index16_bits,
+    bit_index16_nb_bits,
+    bit_index16_nb_elements,
+    bit_index16_contains,
+    bit_index16_set_bit,
+    bit_index16_unset_bit,
+    bit_index16_smallest,
+    bit_index16_largest,
+    bit_index16_pop_smallest,
+    bit_index16_pop_largest
+);
+impl_ffi!(
+    BitIndex32,
+    u32,
+    bit_index32_new,
+    bit_index32_empty,
+    bit_index32_bits,
+    bit_index32_nb_bits,
+    bit_index32_nb_elements,
+    bit_index32_contains,
+    bit_index32_set_bit,
+    bit_index32_unset_bit,
+    bit_index32_smallest,
+    bit_index32_largest,
+    bit_index32_pop_smallest,
+    bit_index32_pop_largest
+);
+impl_ffi!(
+    BitIndex64,
+    u64,
+    bit_index64_new,
+    bit_index64_empty,
+    bit_index64_bits,
+    bit_index64_nb_bits,
+    bit_index64_nb_elements,
+    bit_index64_contains,
+    bit_index64_set_bit,
+    bit_index64_unset_bit,
+    bit_index64_smallest,
+    bit_index64_largest,
+    bit_index64_pop_smallest,
+    bit_index64_pop_largest
+);
+impl_ffi!(
+    BitIndex128,
+    u128,
+    bit_index128_new,
+    bit_index128_empty,
+    bit_index128_bits,
+    bit_index128_nb_bits,
+    bit_index128_nb_elements,
+    bit_index128_contains,
+    bit_index128_set_bit,
+    bit_index128_unset_bit,
+    bit_index128_smallest,
+    bit_index128_largest,
+    bit_index128_pop_smallest,
+    bit_index128_pop_largest
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let mut bi = BitIndex8::empty(0).unwrap();
+        assert!(!bit_index8_new(9, Some(&mut bi)));
+        assert!(!bit_index8_new(4, None));
+        assert!(bit_index8_new(4, Some(&mut bi)));
+        assert_eq!(0b1111, bit_index8_bits(bi));
+        assert_eq!(4, bit_index8_nb_bits(bi));
+
+        assert!(bit_index8_unset_bit(Some(&mut bi), 0));
+        assert!(!bit_index8_unset_bit(Some(&mut bi), 4));
+        assert!(!bit_index8_contains(bi, 0));
+        assert!(bit_index8_contains(bi, 1));
+        assert!(!bit_index8_contains(bi, 7));
+
+        let mut out = 0;
+        assert!(bit_index8_smallest(bi, Some(&mut out)));
+        assert_eq!(1, out);
+        assert!(bit_index8_pop_largest(Some(&mut bi), Some(&mut out)));
+        assert_eq!(3, out);
+        assert_eq!(2, bit_index8_nb_elements(bi));
+    }
+
+    #[test]
+    fn empty() {
+        let mut bi = BitIndex128::new(0).unwrap();
+        assert!(bit_index128_empty(100, Some(&mut bi)));
+        let mut out = 42;
+        assert!(!bit_index128_largest(bi, Some(&mut out)));
+        assert!(!bit_index128_pop_smallest(Some(&mut bi), Some(&mut out)));
+        assert_eq!(42, out);
+        assert!(bit_index128_set_bit(Some(&mut bi), 99));
+        assert!(bit_index128_largest(bi, Some(&mut out)));
+        assert_eq!(99, out);
+    }
+}
@@ -0,0 +1,61 @@
+//! Conversions from and to `bitflags` types, enabled with the `bitflags` feature.
+
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+use bitflags::Flags;
+
+macro_rules! impl_flags {
+    ($bit_index_name:ident, $bit_index_type:ty) => {
+        impl $bit_index_name {
+            /// The bits of `flags`, with `nb_bits` up to the highest defined flag
+            /// or the highest set bit, whichever is higher.
+            pub fn from_flags<F: Flags<Bits = $bit_index_type>>(flags: &F) -> Self {
+                let span = flags.bits() | F::all().bits();
+                Self {
+                    bits: flags.bits(),
+                    nb_bits: Self::SIZE - span.leading_zeros() as u8,
+                }
+            }
+
+            /// The flags with the same bits, or `None` if a set bit is not a defined flag.
+            pub fn to_flags<F: Flags<Bits = $bit_index_type>>(&self) -> Option<F> {
+                F::from_bits(self.bits)
+            }
+        }
+    };
+}
+
+impl_flags!(BitIndex8, u8);
+impl_flags!(BitIndex16, u16);
+impl_flags!(BitIndex32, u32);
+impl_flags!(BitIndex64, u64);
+impl_flags!(BitIndex128, u128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    bitflags::bitflags! {
+        #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+        struct Perms: u16 {
+            const READ = 1;
+            const WRITE = 1 << 1;
+            const EXEC = 1 << 4;
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let perms = Perms::READ | Perms::EXEC;
+        let mut bi = BitIndex16::from_flags(&perms);
+        assert_eq!((0b10001, 5), (bi.unwrap(), bi.nb_bits()));
+        assert_eq!(Some(4), bi.pop_largest());
+        assert_eq!(Some(Perms::READ), bi.to_flags());
+
+        bi.set_bit(2);
+        assert_eq!(None, bi.to_flags::<Perms>());
+
+        let unknown = BitIndex16::from_flags(&Perms::from_bits_retain(1 << 9));
+        assert_eq!(10, unknown.nb_bits());
+        assert!(BitIndex16::from_flags(&Perms::empty()).is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{BitIndex, Lattice};
+
+/// A graph on up to 128 vertices, stored as one adjacency row per vertex.
+///
+/// Row `v` has bit `w` set when there is an arc from `v` to `w`. Searches expand a whole
+/// frontier at once by joining the rows of its vertices.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BitGraph<B> {
+    adjacency: Vec<B>,
+}
+
+impl<B: BitIndex + Lattice> BitGraph<B> {
+    /// A graph with `nb_vertices` vertices and no arcs.
+    pub fn new(nb_vertices: u8) -> Result<Self, String> {
+        B::empty(nb_vertices).map(|row| Self {
+            adjacency: vec![row; nb_vertices as usize],
+        })
+    }
+
+    pub fn nb_vertices(&self) -> u8 {
+        self.adjacency.len() as u8
+    }
+
+    pub fn add_arc(&mut self, from: u8, to: u8) {
+        self.row_mut(from).set_bit(to);
+    }
+
+    pub fn remove_arc(&mut self, from: u8, to: u8) {
+        self.row_mut(from).unset_bit(to);
+    }
+
+    /// Adds the arcs in both directions.
+    pub fn add_edge(&mut self, a: u8, b: u8) {
+        self.add_arc(a, b);
+        self.add_arc(b, a);
+    }
+
+    pub fn remove_edge(&mut self, a: u8, b: u8) {
+        self.remove_arc(a, b);
+        self.remove_arc(b, a);
+    }
+
+    pub fn has_arc(&self, from: u8, to: u8) -> bool {
+        self.neighbors(from).contains(to)
+    }
+
+    /// The vertices `v` has an arc to.
+    pub fn neighbors(&self, v: u8) -> B {
+        *self.row(v)
+    }
+
+    /// The number of arcs leaving `v`.
+    pub fn degree(&self, v: u8) -> u8 {
+        self.row(v).nb_elements()
+    }
+
+    /// The number of arcs arriving at `v`.
+    pub fn in_degree(&self, v: u8) -> u8 {
+        self.adjacency.iter().filter(|row| row.contains(v)).count() as u8
+    }
+
+    /// The vertices reachable from `from`, `from` included.
+    pub fn bfs(&self, from: u8) -> B {
+        let mut reached = self.row(from).bottom();
+        reached.set_bit(from);
+        let mut frontier = reached;
+        while !frontier.is_empty() {
+            let mut next = reached.bottom();
+            while let Some(v) = frontier.pop_smallest() {
+                next = next.join(self.row(v));
+            }
+            frontier = next.difference(&reached);
+            reached = reached.join(&frontier);
+        }
+        reached
+    }
+
+    /// The connected components, ordered by their smallest vertex.
+    /// Arcs are followed in their direction, so this is meant for graphs built with `add_edge`.
+    pub fn connected_components(&self) -> Vec<B> {
+        let mut components = Vec::new();
+        let mut remaining = match self.adjacency.first() {
+            Some(row) => row.top(),
+            None => return components,
+        };
+        while let Some(v) = remaining.smallest() {
+            let component = self.bfs(v);
+            remaining = remaining.difference(&component);
+            components.push(component);
+        }
+        components
+    }
+
+    /// The graph with an arc from `v` to every vertex reachable from `v` by a path of one or
+    /// more arcs, by Warshall's algorithm: a vertex reaching `k` also reaches the row of `k`.
+    pub fn transitive_closure(&self) -> Self {
+        let mut closure = self.clone();
+        for k in 0..self.nb_vertices() {
+            let row_k = closure.adjacency[k as usize];
+            for row in closure.adjacency.iter_mut() {
+                if row.contains(k) {
+                    *row = row.join(&row_k);
+                }
+            }
+        }
+        closure
+    }
+
+    /// All maximal cliques, found by Bron–Kerbosch with pivoting. Self-loops are ignored.
+    /// Meant for graphs built with `add_edge`.
+    pub fn maximal_cliques(&self) -> Vec<B> {
+        let mut cliques = Vec::new();
+        if let Some(row) = self.adjacency.first() {
+            self.bron_kerbosch(row.bottom(), row.top(), row.bottom(), &mut cliques);
+        }
+        cliques
+    }
+
+    // `clique` is extended with vertices of `candidates`; those in `excluded` were tried already.
+    fn bron_kerbosch(&self, clique: B, mut candidates: B, mut excluded: B, cliques: &mut Vec<B>) {
+        let pivot = candidates.join(&excluded);
+        let pivot = match vertices(pivot)
+            .max_by_key(|&u| candidates.meet(&self.proper_neighbors(u)).nb_elements())
+        {
+            Some(pivot) => pivot,
+            None => {
+                cliques.push(clique);
+                return;
+            }
+        };
+        for v in vertices(candidates.difference(&self.proper_neighbors(pivot))) {
+            let neighbors = self.proper_neighbors(v);
+            let mut grown = clique;
+            grown.set_bit(v);
+            self.bron_kerbosch(
+                grown,
+                candidates.meet(&neighbors),
+                excluded.meet(&neighbors),
+                cliques,
+            );
+            candidates.unset_bit(v);
+            excluded.set_bit(v);
+        }
+    }
+
+    fn proper_neighbors(&self, v: u8) -> B {
+        let mut neighbors = self.neighbors(v);
+        neighbors.unset_bit(v);
+        neighbors
+    }
+
+    fn row(&self, v: u8) -> &B {
+        self.check_vertex(v);
+        &self.adjacency[v as usize]
+    }
+
+    fn row_mut(&mut self, v: u8) -> &mut B {
+        self.check_vertex(v);
+        &mut self.adjacency[v as usize]
+    }
+
+    fn check_vertex(&self, v: u8) {
+        if v as usize >= self.adjacency.len() {
+            panic!(
+                "This BitGraph only has {} vertices, not {}",
+                self.adjacency.len(),
+                v
+            );
+        }
+    }
+}
+
+fn vertices<B: BitIndex>(mut set: B) -> impl Iterator<Item = u8> {
+    std::iter::from_fn(move || set.pop_smallest())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitIndex128, BitIndex64, BitIndex8};
+
+    #[test]
+    fn arcs_and_degrees() {
+        let mut graph = BitGraph::<BitIndex8>::new(4).unwrap();
+        graph.add_arc(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_arc(3, 2);
+        assert!(graph.has_arc(0, 1) && !graph.has_arc(1, 0));
+        assert_eq!((1, 2), (graph.degree(1), graph.in_degree(2)));
+        assert_eq!(0b0100, graph.neighbors(3).unwrap());
+
+        graph.remove_edge(1, 2);
+        assert_eq!((0, 1), (graph.degree(1), graph.in_degree(2)));
+    }
+
+    #[test]
+    fn bfs() {
+        let mut graph = BitGraph::<BitIndex8>::new(6).unwrap();
+        graph.add_arc(0, 1);
+        graph.add_arc(1, 2);
+        graph.add_arc(2, 0);
+        graph.add_arc(2, 3);
+        graph.add_arc(4, 5);
+        assert_eq!(0b1111, graph.bfs(1).unwrap());
+        assert_eq!(0b1000, graph.bfs(3).unwrap());
+        assert_eq!(0b110000, graph.bfs(4).unwrap());
+    }
+
+    #[test]
+    fn components() {
+        let mut graph = BitGraph::<BitIndex128>::new(100).unwrap();
+        for v in 0..49 {
+            graph.add_edge(v, v + 1);
+        }
+        graph.add_edge(60, 99);
+        let components = graph.connected_components();
+        assert_eq!(50, components.len());
+        assert_eq!(50, components[0].nb_elements());
+        assert_eq!((1u128 << 60) | (1 << 99), components[11].unwrap());
+        assert!(BitGraph::<BitIndex8>::new(0)
+            .unwrap()
+            .connected_components()
+            .is_empty());
+    }
+
+    #[test]
+    fn closure() {
+        // 0 -> 1 -> 2 -> 0 and 2 -> 3 -> 4
+        let mut graph = BitGraph::<BitIndex64>::new(6).unwrap();
+        for &(from, to) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)].iter() {
+            graph.add_arc(from, to);
+        }
+        let closure = graph.transitive_closure();
+        assert_eq!(0b11111, closure.neighbors(1).unwrap());
+        assert_eq!(0b10000, closure.neighbors(3).unwrap());
+        assert!(closure.neighbors(4).is_empty() && closure.neighbors(5).is_empty());
+        for v in 0..6 {
+            let mut reachable = graph.bfs(v);
+            if !closure.has_arc(v, v) {
+                reachable.unset_bit(v);
+            }
+            assert_eq!(reachable, closure.neighbors(v));
+        }
+    }
+
+    #[test]
+    fn cliques() {
+        // a triangle 0-1-2 sharing vertex 2 with the square 2-3-4-5, plus an isolated 6
+        let mut graph = BitGraph::<BitIndex8>::new(7).unwrap();
+        for &(a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 2)].iter() {
+            graph.add_edge(a, b);
+        }
+        graph.add_arc(6, 6);
+        let mut cliques: Vec<_> = graph
+            .maximal_cliques()
+            .iter()
+            .map(|clique| clique.unwrap())
+            .collect();
+        cliques.sort_unstable();
+        assert_eq!(
+            vec![0b0000111, 0b0001100, 0b0011000, 0b0100100, 0b0110000, 0b1000000],
+            cliques
+        );
+
+        let mut complete = BitGraph::<BitIndex128>::new(128).unwrap();
+        for a in 0..128 {
+            for b in a + 1..128 {
+                complete.add_edge(a, b);
+            }
+        }
+        assert_eq!(
+            vec![u128::MAX],
+            complete
+                .maximal_cliques()
+                .iter()
+                .map(|c| c.unwrap())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        BitGraph::<BitIndex8>::new(3).unwrap().degree(3);
+    }
+}
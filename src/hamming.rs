@@ -0,0 +1,190 @@
+use crate::BitIndex64;
+use std::collections::{BinaryHeap, HashMap};
+
+const NB_CHUNKS: usize = 4;
+
+/// Similarity search over many `BitIndex64`, by Hamming distance between their bits.
+///
+/// Masks are bucketed by number of elements, which bounds their distance to a query from below,
+/// and each 16-bit chunk is hashed: two masks at distance under 4 agree on at least one chunk.
+/// Results are `(id, distance)` pairs sorted by distance, then by id; ids count insertions from 0.
+#[derive(Clone, Default, Debug)]
+pub struct HammingIndex {
+    masks: Vec<BitIndex64>,
+    by_nb_elements: Vec<Vec<usize>>,
+    chunks: [HashMap<u16, Vec<usize>>; NB_CHUNKS],
+}
+
+impl HammingIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.masks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.masks.is_empty()
+    }
+
+    pub fn get(&self, id: usize) -> Option<&BitIndex64> {
+        self.masks.get(id)
+    }
+
+    /// Adds `mask` and returns its id.
+    pub fn insert(&mut self, mask: BitIndex64) -> usize {
+        let id = self.masks.len();
+        let nb_elements = mask.nb_elements() as usize;
+        if self.by_nb_elements.len() <= nb_elements {
+            self.by_nb_elements.resize(nb_elements + 1, Vec::new());
+        }
+        self.by_nb_elements[nb_elements].push(id);
+        for (chunk, ids) in self.chunks.iter_mut().enumerate() {
+            ids.entry(chunk_of(&mask, chunk)).or_default().push(id);
+        }
+        self.masks.push(mask);
+        id
+    }
+
+    /// The `k` masks closest to `query`.
+    /// Buckets are visited outwards from the number of elements of `query`, and the search stops
+    /// once the difference in number of elements exceeds the `k`-th best distance.
+    pub fn nearest(&self, query: &BitIndex64, k: usize) -> Vec<(usize, u8)> {
+        let mut best: BinaryHeap<(u8, usize)> = BinaryHeap::new();
+        if k == 0 {
+            return Vec::new();
+        }
+        let nb_elements = query.nb_elements() as usize;
+        for delta in 0..=64 {
+            if best.len() == k
+                && best
+                    .peek()
+                    .is_some_and(|&(worst, _)| delta > worst as usize)
+            {
+                break;
+            }
+            let below = nb_elements.checked_sub(delta);
+            let above = Some(nb_elements + delta).filter(|_| delta > 0);
+            for bucket in below.into_iter().chain(above) {
+                for &id in self.bucket(bucket) {
+                    best.push((distance(query, &self.masks[id]), id));
+                    if best.len() > k {
+                        best.pop();
+                    }
+                }
+            }
+        }
+        let mut res: Vec<_> = best.into_iter().map(|(dist, id)| (id, dist)).collect();
+        res.sort_unstable_by_key(|&(id, dist)| (dist, id));
+        res
+    }
+
+    /// All masks at distance `max_distance` or less from `query`.
+    pub fn within_distance(&self, query: &BitIndex64, max_distance: u8) -> Vec<(usize, u8)> {
+        let mut res: Vec<_> = if (max_distance as usize) < NB_CHUNKS {
+            let mut candidates: Vec<usize> = (0..NB_CHUNKS)
+                .filter_map(|chunk| self.chunks[chunk].get(&chunk_of(query, chunk)))
+                .flatten()
+                .copied()
+                .collect();
+            candidates.sort_unstable();
+            candidates.dedup();
+            candidates
+        } else {
+            let nb_elements = query.nb_elements() as usize;
+            let max_distance = max_distance as usize;
+            (nb_elements.saturating_sub(max_distance)..=nb_elements + max_distance)
+                .flat_map(|bucket| self.bucket(bucket))
+                .copied()
+                .collect()
+        }
+        .into_iter()
+        .map(|id| (id, distance(query, &self.masks[id])))
+        .filter(|&(_, dist)| dist <= max_distance)
+        .collect();
+        res.sort_unstable_by_key(|&(id, dist)| (dist, id));
+        res
+    }
+
+    fn bucket(&self, nb_elements: usize) -> &[usize] {
+        self.by_nb_elements
+            .get(nb_elements)
+            .map_or(&[], |ids| &ids[..])
+    }
+}
+
+fn chunk_of(mask: &BitIndex64, chunk: usize) -> u16 {
+    (mask.bits >> (16 * chunk)) as u16
+}
+
+fn distance(a: &BitIndex64, b: &BitIndex64) -> u8 {
+    (a.bits ^ b.bits).count_ones() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::xorshift;
+
+    fn mask(bits: u64) -> BitIndex64 {
+        let mut bi = BitIndex64::empty(64).unwrap();
+        bi.add(bits);
+        bi
+    }
+
+    fn brute_force(masks: &[BitIndex64], query: &BitIndex64) -> Vec<(usize, u8)> {
+        let mut res: Vec<_> = masks
+            .iter()
+            .enumerate()
+            .map(|(id, m)| (id, distance(query, m)))
+            .collect();
+        res.sort_unstable_by_key(|&(id, dist)| (dist, id));
+        res
+    }
+
+    #[test]
+    fn small() {
+        let mut index = HammingIndex::new();
+        assert!(index.nearest(&mask(0), 3).is_empty());
+        for &bits in [0b1111, 0b0111, 0xFF00, 0b1_0000_0111].iter() {
+            index.insert(mask(bits));
+        }
+        assert_eq!(4, index.len());
+        assert_eq!(vec![(1, 0), (0, 1)], index.nearest(&mask(0b0111), 2));
+        assert_eq!(
+            vec![(1, 0), (0, 1), (3, 1)],
+            index.within_distance(&mask(0b0111), 1)
+        );
+        assert_eq!(vec![(2, 0)], index.within_distance(&mask(0xFF00), 7));
+        assert!(index.nearest(&mask(0), 0).is_empty());
+    }
+
+    #[test]
+    fn against_brute_force() {
+        let mut next = xorshift(0x9E37_79B9_7F4A_7C15);
+        let masks: Vec<_> = (0..300)
+            .map(|i| mask(if i % 3 == 0 { next() } else { next() & next() }))
+            .collect();
+        let mut index = HammingIndex::new();
+        for &m in masks.iter() {
+            index.insert(m);
+        }
+        for _ in 0..20 {
+            let query = mask(next() & next());
+            let expected = brute_force(&masks, &query);
+            assert_eq!(expected[..5], index.nearest(&query, 5)[..]);
+            for &d in [0, 3, 12, 20].iter() {
+                let within: Vec<_> = expected
+                    .iter()
+                    .copied()
+                    .filter(|&(_, dist)| dist <= d)
+                    .collect();
+                assert_eq!(within, index.within_distance(&query, d));
+            }
+        }
+        // near-duplicates are found through the chunk tables
+        let near = mask(masks[7].bits ^ 0b101);
+        assert_eq!(Some(&(7, 2)), index.within_distance(&near, 2).first());
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+
+/// A fieldless enum whose variants map to bit numbers `0..NB_VARIANTS`.
+///
+/// With the `derive` feature, `#[derive(BitIndexable)]` implements it in declaration order,
+/// together with `From<Enum>` for every width that can keep all variants.
+pub trait BitIndexable: Copy {
+    const NB_VARIANTS: u8;
+
+    fn bit_nb(self) -> u8;
+    fn from_bit_nb(bit_nb: u8) -> Option<Self>;
+}
+
+macro_rules! impl_indexable {
+    ($bit_index_name:ident) => {
+        impl $bit_index_name {
+            /// An index over all variants of `E`, containing only `variant`.
+            pub fn from_variant<E: BitIndexable>(variant: E) -> Result<Self, String> {
+                Self::empty(E::NB_VARIANTS).map(|mut bi| {
+                    bi.set_bit(variant.bit_nb());
+                    bi
+                })
+            }
+
+            pub fn contains_variant<E: BitIndexable>(&self, variant: E) -> bool {
+                self.contains(variant.bit_nb())
+            }
+
+            pub fn insert_variant<E: BitIndexable>(&mut self, variant: E) {
+                self.set_bit(variant.bit_nb());
+            }
+
+            pub fn remove_variant<E: BitIndexable>(&mut self, variant: E) {
+                self.unset_bit(variant.bit_nb());
+            }
+
+            /// The variants in the index, in bit order. Bits without a variant are skipped.
+            pub fn variants<E: BitIndexable>(&self) -> impl Iterator<Item = E> {
+                let mut remaining = *self;
+                std::iter::from_fn(move || remaining.pop_smallest()).filter_map(E::from_bit_nb)
+            }
+        }
+    };
+}
+
+impl_indexable!(BitIndex8);
+impl_indexable!(BitIndex16);
+impl_indexable!(BitIndex32);
+impl_indexable!(BitIndex64);
+impl_indexable!(BitIndex128);
@@ -0,0 +1,103 @@
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+use std::cmp::max;
+
+macro_rules! impl_interleave {
+    ($wide_name:ident, $wide_type:ty, $narrow_name:ident, $narrow_type:ty) => {
+        impl $wide_name {
+            /// Bits of `a` and `b` alternating: `a0, b0, a1, b1, ...`, with `nb_bits` twice the larger of both.
+            pub fn interleave(a: $narrow_name, b: $narrow_name) -> Self {
+                Self {
+                    bits: (spread(a.bits as u64) | spread(b.bits as u64) << 1) as $wide_type,
+                    nb_bits: 2 * max(a.nb_bits, b.nb_bits),
+                }
+            }
+
+            /// Even bits go to the first index, odd bits to the second.
+            ///
+            /// This undoes `interleave` for operands of equal `nb_bits`. Otherwise both halves
+            /// come back with the common `nb_bits` of the larger operand.
+            pub fn deinterleave(&self) -> ($narrow_name, $narrow_name) {
+                let bits = self.bits as u128;
+                (
+                    $narrow_name {
+                        bits: compact(bits) as $narrow_type,
+                        nb_bits: self.nb_bits.div_ceil(2),
+                    },
+                    $narrow_name {
+                        bits: compact(bits >> 1) as $narrow_type,
+                        nb_bits: self.nb_bits / 2,
+                    },
+                )
+            }
+        }
+    };
+}
+
+impl_interleave!(BitIndex16, u16, BitIndex8, u8);
+impl_interleave!(BitIndex32, u32, BitIndex16, u16);
+impl_interleave!(BitIndex64, u64, BitIndex32, u32);
+impl_interleave!(BitIndex128, u128, BitIndex64, u64);
+
+/// Moves bit `i` to bit `2i`.
+fn spread(x: u64) -> u128 {
+    let mut x = x as u128;
+    x = (x | x << 32) & 0x0000_0000_FFFF_FFFF_0000_0000_FFFF_FFFF;
+    x = (x | x << 16) & 0x0000_FFFF_0000_FFFF_0000_FFFF_0000_FFFF;
+    x = (x | x << 8) & 0x00FF_00FF_00FF_00FF_00FF_00FF_00FF_00FF;
+    x = (x | x << 4) & 0x0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F;
+    x = (x | x << 2) & 0x3333_3333_3333_3333_3333_3333_3333_3333;
+    (x | x << 1) & 0x5555_5555_5555_5555_5555_5555_5555_5555
+}
+
+/// Moves bit `2i` to bit `i`, dropping the odd bits.
+fn compact(x: u128) -> u64 {
+    let mut x = x & 0x5555_5555_5555_5555_5555_5555_5555_5555;
+    x = (x | x >> 1) & 0x3333_3333_3333_3333_3333_3333_3333_3333;
+    x = (x | x >> 2) & 0x0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F;
+    x = (x | x >> 4) & 0x00FF_00FF_00FF_00FF_00FF_00FF_00FF_00FF;
+    x = (x | x >> 8) & 0x0000_FFFF_0000_FFFF_0000_FFFF_0000_FFFF;
+    x = (x | x >> 16) & 0x0000_0000_FFFF_FFFF_0000_0000_FFFF_FFFF;
+    (x | x >> 32) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleave() {
+        let mut a = BitIndex8::empty(4).unwrap();
+        a.set_bits(&[0, 3]);
+        let mut b = BitIndex8::empty(4).unwrap();
+        b.set_bits(&[0, 1]);
+        let both = BitIndex16::interleave(a, b);
+        assert_eq!(0b0100_1011, both.unwrap());
+        assert_eq!(8, both.nb_bits());
+        assert_eq!((a, b), both.deinterleave());
+    }
+
+    #[test]
+    fn mismatched_nb_bits() {
+        let both = BitIndex16::interleave(BitIndex8::new(3).unwrap(), BitIndex8::new(5).unwrap());
+        assert_eq!(10, both.nb_bits());
+        let (a, b) = both.deinterleave();
+        assert_eq!((0b111, 5), (a.unwrap(), a.nb_bits()));
+        assert_eq!(BitIndex8::new(5).unwrap(), b);
+    }
+
+    #[test]
+    fn full_width() {
+        let a = BitIndex64::new(64).unwrap();
+        let b = BitIndex64::empty(64).unwrap();
+        let both = BitIndex128::interleave(a, b);
+        assert_eq!(0x5555_5555_5555_5555_5555_5555_5555_5555, both.unwrap());
+        assert_eq!(128, both.nb_bits());
+        assert_eq!((a, b), both.deinterleave());
+
+        let mut odd = BitIndex32::new(5).unwrap();
+        odd.unset_bit(2);
+        let (even_bits, odd_bits) = odd.deinterleave();
+        assert_eq!((0b101, 3), (even_bits.unwrap(), even_bits.nb_bits()));
+        assert_eq!((0b11, 2), (odd_bits.unwrap(), odd_bits.nb_bits()));
+    }
+}
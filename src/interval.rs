@@ -0,0 +1,154 @@
+use crate::BitIndex;
+use std::ops::RangeInclusive;
+
+/// A `BitIndex` seen as a set of disjoint, non-adjacent inclusive intervals.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct IntervalSet<B> {
+    index: B,
+}
+
+impl<B: BitIndex> IntervalSet<B> {
+    /// An empty interval set over `0..nb_bits`.
+    pub fn new(nb_bits: u8) -> Result<Self, String> {
+        B::empty(nb_bits).map(|index| Self { index })
+    }
+
+    pub fn index(&self) -> &B {
+        &self.index
+    }
+
+    pub fn into_index(self) -> B {
+        self.index
+    }
+
+    pub fn contains(&self, idx: u8) -> bool {
+        self.index.contains(idx)
+    }
+
+    pub fn insert_range(&mut self, range: RangeInclusive<u8>) {
+        if range.start() <= range.end() {
+            let end = self.exclusive_end(*range.end());
+            self.index.set_from_ranges(Some(*range.start()..end));
+        }
+    }
+
+    pub fn remove_range(&mut self, range: RangeInclusive<u8>) {
+        if range.start() <= range.end() {
+            let end = self.exclusive_end(*range.end());
+            self.index.unset_from_ranges(Some(*range.start()..end));
+        }
+    }
+
+    /// The maximal interval containing `idx`, if `idx` is in the set.
+    pub fn covering_interval(&self, idx: u8) -> Option<RangeInclusive<u8>> {
+        if !self.index.contains(idx) {
+            return None;
+        }
+        self.intervals().find(|interval| interval.contains(&idx))
+    }
+
+    /// The intervals in increasing order.
+    pub fn intervals(&self) -> impl Iterator<Item = RangeInclusive<u8>> {
+        Runs {
+            bits: self.index.to_u128() & self.mask(),
+        }
+    }
+
+    /// The maximal intervals of `0..nb_bits` not in the set, in increasing order.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<u8>> {
+        Runs {
+            bits: !self.index.to_u128() & self.mask(),
+        }
+    }
+
+    fn exclusive_end(&self, end: u8) -> u8 {
+        if end >= self.index.nb_bits() {
+            panic!(
+                "This IntervalSet can only handle inputs upto {}",
+                self.index.nb_bits()
+            );
+        }
+        end + 1
+    }
+
+    fn mask(&self) -> u128 {
+        match self.index.nb_bits() {
+            128 => u128::MAX,
+            nb_bits => (1 << nb_bits) - 1,
+        }
+    }
+}
+
+impl<B: BitIndex> From<B> for IntervalSet<B> {
+    fn from(index: B) -> Self {
+        Self { index }
+    }
+}
+
+struct Runs {
+    bits: u128,
+}
+
+impl Iterator for Runs {
+    type Item = RangeInclusive<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bits == 0 {
+            return None;
+        }
+        let start = self.bits.trailing_zeros();
+        let end = start + (!(self.bits >> start)).trailing_zeros();
+        self.bits = if end == 128 {
+            0
+        } else {
+            self.bits & !((1 << end) - 1)
+        };
+        Some(start as u8..=(end - 1) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitIndex128, BitIndex16};
+
+    #[test]
+    fn insert_remove() {
+        let mut set = IntervalSet::<BitIndex16>::new(12).unwrap();
+        set.insert_range(2..=4);
+        set.insert_range(5..=6);
+        set.insert_range(9..=11);
+        assert_eq!(vec![2..=6, 9..=11], set.intervals().collect::<Vec<_>>());
+        assert_eq!(vec![0..=1, 7..=8], set.gaps().collect::<Vec<_>>());
+
+        set.remove_range(4..=4);
+        assert_eq!(Some(5..=6), set.covering_interval(6));
+        assert_eq!(Some(2..=3), set.covering_interval(2));
+        assert_eq!(None, set.covering_interval(4));
+        assert_eq!(0b111001101100, set.into_index().unwrap());
+    }
+
+    #[test]
+    fn full_width() {
+        let set = IntervalSet::from(BitIndex128::new(128).unwrap());
+        assert_eq!(vec![0..=127], set.intervals().collect::<Vec<_>>());
+        assert_eq!(0, set.gaps().count());
+        assert_eq!(Some(0..=127), set.covering_interval(64));
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        IntervalSet::<BitIndex16>::new(12)
+            .unwrap()
+            .insert_range(10..=12);
+    }
+
+    #[test]
+    #[should_panic(expected = "can only handle inputs upto 128")]
+    fn end_at_u8_max() {
+        IntervalSet::<BitIndex128>::new(128)
+            .unwrap()
+            .remove_range(0..=255);
+    }
+}
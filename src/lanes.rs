@@ -0,0 +1,169 @@
+use crate::BitIndex;
+
+/// How `LaneAllocator::allocate` picks a lane.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum LanePolicy {
+    /// The lane with the fewest allocated slots, the first one on ties.
+    LeastLoaded,
+    /// The next lane with a free slot after the previously picked one.
+    RoundRobin,
+}
+
+/// The load across the lanes of a `LaneAllocator`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LaneSummary {
+    pub nb_allocated: usize,
+    pub capacity: usize,
+    pub min_load: u8,
+    pub max_load: u8,
+}
+
+/// Slot allocation over several `BitIndex` lanes, as in a sharded resource pool.
+///
+/// A set bit is an allocated slot. Within a lane, the smallest free slot is handed out.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LaneAllocator<B> {
+    lanes: Vec<B>,
+    policy: LanePolicy,
+    last: Option<usize>,
+}
+
+impl<B: BitIndex> LaneAllocator<B> {
+    /// `nb_lanes` lanes of `nb_slots` free slots each.
+    pub fn new(nb_lanes: usize, nb_slots: u8, policy: LanePolicy) -> Result<Self, String> {
+        B::empty(nb_slots).map(|lane| Self {
+            lanes: vec![lane; nb_lanes],
+            policy,
+            last: None,
+        })
+    }
+
+    pub fn lanes(&self) -> &[B] {
+        &self.lanes
+    }
+
+    /// Takes a free slot and returns its `(lane, slot)`, or `None` if every lane is full.
+    pub fn allocate(&mut self) -> Option<(usize, u8)> {
+        let lane = match self.policy {
+            LanePolicy::LeastLoaded => (0..self.lanes.len())
+                .filter(|&lane| free_slot(&self.lanes[lane]).is_some())
+                .min_by_key(|&lane| self.lanes[lane].nb_elements()),
+            LanePolicy::RoundRobin => {
+                let start = self.last.map_or(0, |last| last + 1);
+                (0..self.lanes.len())
+                    .map(|i| (start + i) % self.lanes.len())
+                    .find(|&lane| free_slot(&self.lanes[lane]).is_some())
+            }
+        }?;
+        let slot = free_slot(&self.lanes[lane])?;
+        self.lanes[lane].set_bit(slot);
+        self.last = Some(lane);
+        Some((lane, slot))
+    }
+
+    /// Frees `slot` of `lane`; freeing a free slot does nothing.
+    pub fn free(&mut self, lane: usize, slot: u8) {
+        self.lane_mut(lane).unset_bit(slot);
+    }
+
+    pub fn is_allocated(&self, lane: usize, slot: u8) -> bool {
+        self.lane(lane).contains(slot)
+    }
+
+    pub fn summary(&self) -> LaneSummary {
+        let loads = self.lanes.iter().map(|lane| lane.nb_elements());
+        LaneSummary {
+            nb_allocated: loads.clone().map(|load| load as usize).sum(),
+            capacity: self.lanes.iter().map(|lane| lane.nb_bits() as usize).sum(),
+            min_load: loads.clone().min().unwrap_or(0),
+            max_load: loads.max().unwrap_or(0),
+        }
+    }
+
+    fn lane(&self, lane: usize) -> &B {
+        self.check_lane(lane);
+        &self.lanes[lane]
+    }
+
+    fn lane_mut(&mut self, lane: usize) -> &mut B {
+        self.check_lane(lane);
+        &mut self.lanes[lane]
+    }
+
+    fn check_lane(&self, lane: usize) {
+        if lane >= self.lanes.len() {
+            panic!(
+                "This LaneAllocator only has {} lanes, not {}",
+                self.lanes.len(),
+                lane
+            );
+        }
+    }
+}
+
+fn free_slot<B: BitIndex>(lane: &B) -> Option<u8> {
+    let mut free = *lane;
+    free.restore();
+    free.difference(lane).smallest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitIndex8;
+
+    #[test]
+    fn least_loaded() {
+        let mut pool = LaneAllocator::<BitIndex8>::new(3, 2, LanePolicy::LeastLoaded).unwrap();
+        let slots: Vec<_> = (0..4).filter_map(|_| pool.allocate()).collect();
+        assert_eq!(vec![(0, 0), (1, 0), (2, 0), (0, 1)], slots);
+
+        pool.free(1, 0);
+        assert!(!pool.is_allocated(1, 0));
+        assert_eq!(Some((1, 0)), pool.allocate());
+        assert_eq!(Some((1, 1)), pool.allocate());
+        assert_eq!(Some((2, 1)), pool.allocate());
+        assert_eq!(None, pool.allocate());
+        assert_eq!(
+            LaneSummary {
+                nb_allocated: 6,
+                capacity: 6,
+                min_load: 2,
+                max_load: 2,
+            },
+            pool.summary()
+        );
+    }
+
+    #[test]
+    fn round_robin() {
+        let mut pool = LaneAllocator::<BitIndex8>::new(3, 4, LanePolicy::RoundRobin).unwrap();
+        pool.allocate();
+        pool.allocate();
+        pool.free(0, 0);
+        let lanes: Vec<_> = (0..4).filter_map(|_| pool.allocate()).collect();
+        assert_eq!(vec![(2, 0), (0, 0), (1, 1), (2, 1)], lanes);
+        let summary = pool.summary();
+        assert_eq!(
+            (5, 12, 1, 2),
+            (
+                summary.nb_allocated,
+                summary.capacity,
+                summary.min_load,
+                summary.max_load
+            )
+        );
+
+        let mut none = LaneAllocator::<BitIndex8>::new(0, 4, LanePolicy::RoundRobin).unwrap();
+        assert_eq!(None, none.allocate());
+        assert_eq!(0, none.summary().capacity);
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_lane() {
+        LaneAllocator::<BitIndex8>::new(2, 4, LanePolicy::LeastLoaded)
+            .unwrap()
+            .free(2, 0);
+    }
+}
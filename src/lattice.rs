@@ -0,0 +1,145 @@
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+use std::cmp::{max, min};
+use std::iter::Sum;
+
+/// The subset lattice of a `BitIndex`: join is union, meet is intersection.
+///
+/// `bottom` and `top` are the empty and full sets within the `nb_bits` of `self`.
+/// Joining widens to the larger `nb_bits` of both operands, meeting narrows to the smaller one.
+pub trait Lattice: Sized {
+    fn join(&self, other: &Self) -> Self;
+    fn meet(&self, other: &Self) -> Self;
+    fn bottom(&self) -> Self;
+    fn top(&self) -> Self;
+}
+
+macro_rules! impl_lattice {
+    ($bit_index_name:ident) => {
+        impl Lattice for $bit_index_name {
+            fn join(&self, other: &Self) -> Self {
+                Self {
+                    bits: self.bits | other.bits,
+                    nb_bits: max(self.nb_bits, other.nb_bits),
+                }
+            }
+
+            fn meet(&self, other: &Self) -> Self {
+                Self {
+                    bits: self.bits & other.bits,
+                    nb_bits: min(self.nb_bits, other.nb_bits),
+                }
+            }
+
+            fn bottom(&self) -> Self {
+                Self {
+                    bits: 0,
+                    nb_bits: self.nb_bits,
+                }
+            }
+
+            fn top(&self) -> Self {
+                Self {
+                    bits: Self::init(self.nb_bits),
+                    nb_bits: self.nb_bits,
+                }
+            }
+        }
+
+        impl $bit_index_name {
+            /// Union of all indexes, with the largest `nb_bits` among them.
+            /// An empty iterator yields the empty index with `nb_bits` zero.
+            pub fn union_all<I: IntoIterator<Item = Self>>(iter: I) -> Self {
+                iter.into_iter().fold(
+                    Self {
+                        bits: 0,
+                        nb_bits: 0,
+                    },
+                    |acc, bi| acc.join(&bi),
+                )
+            }
+
+            /// Intersection of all indexes, with the smallest `nb_bits` among them.
+            /// Returns `None` for an empty iterator, as there is no width to build a full index with.
+            pub fn intersect_all<I: IntoIterator<Item = Self>>(iter: I) -> Option<Self> {
+                let mut iter = iter.into_iter();
+                iter.next()
+                    .map(|first| iter.fold(first, |acc, bi| acc.meet(&bi)))
+            }
+        }
+
+        impl Sum for $bit_index_name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self::union_all(iter)
+            }
+        }
+
+        impl<'a> Sum<&'a $bit_index_name> for $bit_index_name {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                Self::union_all(iter.copied())
+            }
+        }
+    };
+}
+
+impl_lattice!(BitIndex8);
+impl_lattice!(BitIndex16);
+impl_lattice!(BitIndex32);
+impl_lattice!(BitIndex64);
+impl_lattice!(BitIndex128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_meet() {
+        let mut a = BitIndex8::new(4).unwrap();
+        a.unset_bit(0);
+        let mut b = BitIndex8::empty(6).unwrap();
+        b.set_bit(0);
+        b.set_bit(5);
+
+        let join = a.join(&b);
+        assert_eq!(0b101111, join.unwrap());
+        assert_eq!(Some(5), join.largest());
+
+        let meet = a.meet(&b);
+        assert!(meet.is_empty());
+        assert_eq!(a, a.join(&a.meet(&b)));
+        assert_eq!(a, a.meet(&a.join(&b)));
+    }
+
+    #[test]
+    fn bottom_top() {
+        let mut bi = BitIndex64::new(40).unwrap();
+        bi.unset_bit(3);
+        assert!(bi.bottom().is_empty());
+        assert_eq!(40, bi.top().nb_elements());
+        assert_eq!(bi, bi.join(&bi.bottom()));
+        assert_eq!(bi, bi.meet(&bi.top()));
+    }
+
+    #[test]
+    fn folding() {
+        let mut a = BitIndex64::empty(10).unwrap();
+        a.set_bit(1);
+        a.set_bit(2);
+        let mut b = BitIndex64::empty(20).unwrap();
+        b.set_bit(2);
+        b.set_bit(15);
+        let masks = [a, b];
+
+        let sum = masks.iter().copied().sum::<BitIndex64>();
+        assert_eq!(0b1000000000000110, sum.unwrap());
+        assert_eq!(Some(15), sum.largest());
+        assert_eq!(sum, masks.iter().sum());
+
+        let inter = BitIndex64::intersect_all(masks.iter().copied()).unwrap();
+        assert_eq!(0b100, inter.unwrap());
+        assert_eq!(inter.meet(&inter.top()), inter);
+
+        let none: [BitIndex64; 0] = [];
+        assert!(none.iter().sum::<BitIndex64>().is_empty());
+        assert_eq!(None, BitIndex64::intersect_all(none));
+    }
+}
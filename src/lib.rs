@@ -1,10 +1,110 @@
 use std::cmp::max;
+use std::convert::TryFrom;
 use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::ops::Range;
+
+mod arena;
+mod bloom;
+#[cfg(feature = "board")]
+pub mod board;
+pub mod bulk;
+mod candidates;
+mod chained;
+mod clock;
+mod collections;
+mod counted;
+mod counter;
+mod cube;
+mod exact_cover;
+mod family;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "bitflags")]
+mod flags;
+mod graph;
+mod hamming;
+mod indexable;
+mod interleave;
+mod interval;
+mod lanes;
+mod lattice;
+mod metered;
+mod mmio;
+mod pattern;
+mod persist;
+mod queue;
+mod round_robin;
+mod stats;
+mod stream;
+#[cfg(test)]
+mod test_util;
+mod typed;
+
+pub use arena::{ArenaHandle, BitSetArena};
+#[cfg(feature = "derive")]
+pub use bit_index_derive::BitIndexable;
+pub use bloom::BloomIndex;
+pub use chained::ChainedBits;
+pub use clock::ClockBits;
+pub use counted::CountedBitIndex;
+pub use counter::{BitCounter16, BitCounter32, BitCounter8};
+pub use cube::BitCube;
+pub use exact_cover::solve_exact_cover;
+pub use family::{
+    greedy_cover, intersection_matrix, invert_collection, remove_subsumed, similar_pairs,
+};
+pub use graph::BitGraph;
+pub use hamming::HammingIndex;
+pub use indexable::BitIndexable;
+pub use interval::IntervalSet;
+pub use lanes::{LaneAllocator, LanePolicy, LaneSummary};
+pub use lattice::Lattice;
+pub use metered::{MeteredBitIndex, OccupancyMetrics};
+pub use mmio::{MmioBits16, MmioBits32, MmioBits64, MmioBits8};
+pub use pattern::BitPattern;
+pub use queue::BitQueue;
+pub use round_robin::RoundRobin;
+pub use stats::Stats;
+pub use stream::{BitReader, BitWriter};
+pub use typed::TypedBitIndex;
+
+/// The operations shared by every width, for code that is generic over the `BitIndex` it uses.
+pub trait BitIndex: Copy + Eq + Hash + Debug {
+    /// The number of bits the width can keep.
+    const SIZE: u8;
+
+    fn new(nb_bits: u8) -> Result<Self, String>;
+    fn empty(nb_bits: u8) -> Result<Self, String>;
+    fn nb_bits(&self) -> u8;
+    fn to_u128(&self) -> u128;
+    fn nb_elements(&self) -> u8;
+    fn is_empty(&self) -> bool;
+    fn clear(&mut self);
+    fn restore(&mut self);
+    fn contains(&self, bit_nb: u8) -> bool;
+    fn set_bit(&mut self, bit_nb: u8);
+    fn unset_bit(&mut self, bit_nb: u8);
+    fn set_from_ranges<I: IntoIterator<Item = Range<u8>>>(&mut self, ranges: I);
+    fn unset_from_ranges<I: IntoIterator<Item = Range<u8>>>(&mut self, ranges: I);
+    fn smallest(&self) -> Option<u8>;
+    fn largest(&self) -> Option<u8>;
+    fn pop_smallest(&mut self) -> Option<u8>;
+    fn pop_largest(&mut self) -> Option<u8>;
+    fn next_set_bit(&self, after: u8) -> Option<u8>;
+    fn next_set_bit_wrapping(&self, after: u8) -> Option<u8>;
+    fn difference(&self, other: &Self) -> Self;
+}
 
 macro_rules! impl_bit_index {
     ($bit_index_name:ident, $bit_index_type:ty) => {
         /// A list of bits to track elements. Little-endian and zero-indexed.`
+        ///
+        /// Out-of-range bit numbers are handled by three method families: the default methods
+        /// (`set_bit`, `contains`, ...) panic, their `try_*` counterparts return an error, and the
+        /// `unsafe` `*_unchecked` methods only check in debug builds.
         #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+        #[repr(C)]
         pub struct $bit_index_name {
             /// The bits to track elements
             bits: $bit_index_type,
@@ -41,11 +141,44 @@ macro_rules! impl_bit_index {
                 self.bits
             }
 
+            #[inline]
+            pub fn nb_bits(&self) -> u8 {
+                self.nb_bits
+            }
+
+            /// The bits widened to `u128`, the common integer type of all widths.
+            #[inline]
+            pub fn to_u128(&self) -> u128 {
+                self.bits as u128
+            }
+
             #[inline]
             pub fn is_empty(&self) -> bool {
                 self.bits == 0
             }
 
+            /// Writes the `nb_bits` bits as `0`/`1`, most significant first, without allocating.
+            pub fn write_bits<W: fmt::Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
+                (0..self.nb_bits).rev().try_for_each(|bit_nb| {
+                    w.write_char(if self.bits >> bit_nb & 1 == 1 {
+                        '1'
+                    } else {
+                        '0'
+                    })
+                })
+            }
+
+            /// Renders the bits like `write_bits` at the start of `buf` and returns the number of bytes written.
+            /// Fails without writing if `buf` is shorter than `nb_bits`.
+            pub fn render_into(&self, buf: &mut [u8]) -> Result<usize, fmt::Error> {
+                let len = self.nb_bits as usize;
+                let buf = buf.get_mut(..len).ok_or(fmt::Error)?;
+                for (i, byte) in buf.iter_mut().enumerate() {
+                    *byte = b'0' + (self.bits >> (len - 1 - i) & 1) as u8;
+                }
+                Ok(len)
+            }
+
             #[inline]
             pub fn clear(&mut self) {
                 self.bits = 0;
@@ -97,6 +230,15 @@ macro_rules! impl_bit_index {
                 })
             }
 
+            /// Python-style indexing: `0` is the smallest element, `-1` the largest, `-2` the second largest, ...
+            pub fn get_signed(&self, idx: i8) -> Option<u8> {
+                if idx < 0 {
+                    self.get_from_high_end((-(idx + 1)) as u8)
+                } else {
+                    self.get_from_low_end(idx as u8)
+                }
+            }
+
             fn get_check(&self, idx: u8) -> Option<u8> {
                 if idx >= self.nb_bits {
                     panic!(
@@ -129,6 +271,12 @@ macro_rules! impl_bit_index {
                 res
             }
 
+            pub fn pop_signed(&mut self, idx: i8) -> Option<u8> {
+                let res = self.get_signed(idx);
+                res.map(|bit_nb| self.unset_bit(bit_nb));
+                res
+            }
+
             pub fn smallest(&self) -> Option<u8> {
                 if self.is_empty() {
                     None
@@ -157,6 +305,50 @@ macro_rules! impl_bit_index {
                 res
             }
 
+            /// Offsets at which the `pattern_len` lowest bits of `pattern` occur in `self`,
+            /// with the whole window within `nb_bits`. An empty pattern matches nowhere.
+            pub fn find_pattern(&self, pattern: Self, pattern_len: u8) -> Vec<u8> {
+                if pattern_len == 0 || pattern_len > self.nb_bits {
+                    return Vec::new();
+                }
+                let window = Self::init(pattern_len);
+                let target = pattern.bits & window;
+                (0..=self.nb_bits - pattern_len)
+                    .filter(|&offset| (self.bits >> offset) & window == target)
+                    .collect()
+            }
+
+            /// The lowest offset of `len` consecutive unset bits within `nb_bits`.
+            pub fn find_zero_run(&self, len: u8) -> Option<u8> {
+                if len > self.nb_bits {
+                    return None;
+                }
+                let free = !self.bits & Self::init(self.nb_bits);
+                let starts = (1..len).fold(free, |starts, shift| starts & (free >> shift));
+                match (len, starts) {
+                    (0, _) => Some(0),
+                    (_, 0) => None,
+                    (_, starts) => Some(starts.trailing_zeros() as u8),
+                }
+            }
+
+            /// The smallest set bit strictly above `after`.
+            pub fn next_set_bit(&self, after: u8) -> Option<u8> {
+                if after >= self.nb_bits {
+                    return None;
+                }
+                match self.bits & Self::init(self.nb_bits) & !Self::init(after + 1) {
+                    0 => None,
+                    bits => Some(bits.trailing_zeros() as u8),
+                }
+            }
+
+            /// The next set bit after `after`, continuing from the smallest one past the end.
+            /// `after` itself is returned when it is the only set bit.
+            pub fn next_set_bit_wrapping(&self, after: u8) -> Option<u8> {
+                self.next_set_bit(after).or_else(|| self.smallest())
+            }
+
             // explicit check not necessary: handled by `single_bit`
             #[inline]
             pub fn set_bit(&mut self, bit_nb: u8) {
@@ -169,21 +361,256 @@ macro_rules! impl_bit_index {
                 self.bits &= self.all_but_single_bit(bit_nb);
             }
 
+            /// Sets all given bits at once. Panics before writing anything if one of them is out of range.
+            pub fn set_bits(&mut self, bit_nbs: &[u8]) {
+                self.bits |= self.mask_of(bit_nbs);
+            }
+
+            /// Unsets all given bits at once. Panics before writing anything if one of them is out of range.
+            pub fn unset_bits(&mut self, bit_nbs: &[u8]) {
+                self.bits &= !self.mask_of(bit_nbs);
+            }
+
+            /// Sets every bit covered by the ranges. Panics before writing anything if a range ends past `nb_bits`.
+            pub fn set_from_ranges<I: IntoIterator<Item = Range<u8>>>(&mut self, ranges: I) {
+                self.bits |= self.mask_of_ranges(ranges);
+            }
+
+            /// Unsets every bit covered by the ranges. Panics before writing anything if a range ends past `nb_bits`.
+            pub fn unset_from_ranges<I: IntoIterator<Item = Range<u8>>>(&mut self, ranges: I) {
+                self.bits &= !self.mask_of_ranges(ranges);
+            }
+
+            #[inline]
+            pub fn contains(&self, bit_nb: u8) -> bool {
+                self.bits & self.single_bit(bit_nb) != 0
+            }
+
+            pub fn try_set_bit(&mut self, bit_nb: u8) -> Result<(), String> {
+                self.check_range(bit_nb).map(|_| self.bits |= 1 << bit_nb)
+            }
+
+            pub fn try_unset_bit(&mut self, bit_nb: u8) -> Result<(), String> {
+                self.check_range(bit_nb)
+                    .map(|_| self.bits &= !(1 << bit_nb))
+            }
+
+            pub fn try_contains(&self, bit_nb: u8) -> Result<bool, String> {
+                self.check_range(bit_nb)
+                    .map(|_| self.bits & (1 << bit_nb) != 0)
+            }
+
+            /// Like `get`, but returns an error instead of panicking when `idx` is out of range.
+            pub fn try_get(&self, idx: u8) -> Result<Option<u8>, String> {
+                self.try_get_from_low_end(idx)
+            }
+
+            pub fn try_get_from_low_end(&self, idx: u8) -> Result<Option<u8>, String> {
+                self.check_range(idx).map(|_| self.get_from_low_end(idx))
+            }
+
+            pub fn try_get_from_high_end(&self, idx: u8) -> Result<Option<u8>, String> {
+                self.check_range(idx).map(|_| self.get_from_high_end(idx))
+            }
+
+            pub fn try_get_signed(&self, idx: i8) -> Result<Option<u8>, String> {
+                let unsigned_idx = if idx < 0 {
+                    (-(idx + 1)) as u8
+                } else {
+                    idx as u8
+                };
+                self.check_range(unsigned_idx).map(|_| self.get_signed(idx))
+            }
+
+            pub fn try_pop(&mut self, idx: u8) -> Result<Option<u8>, String> {
+                self.try_pop_from_low_end(idx)
+            }
+
+            pub fn try_pop_from_low_end(&mut self, idx: u8) -> Result<Option<u8>, String> {
+                self.check_range(idx).map(|_| self.pop_from_low_end(idx))
+            }
+
+            pub fn try_pop_from_high_end(&mut self, idx: u8) -> Result<Option<u8>, String> {
+                self.check_range(idx).map(|_| self.pop_from_high_end(idx))
+            }
+
+            pub fn try_pop_signed(&mut self, idx: i8) -> Result<Option<u8>, String> {
+                let res = self.try_get_signed(idx)?;
+                res.map(|bit_nb| self.unset_bit(bit_nb));
+                Ok(res)
+            }
+
+            /// Like `set_bits`, but returns an error, without writing anything, instead of panicking.
+            pub fn try_set_bits(&mut self, bit_nbs: &[u8]) -> Result<(), String> {
+                self.try_mask_of(bit_nbs).map(|mask| self.bits |= mask)
+            }
+
+            /// Like `unset_bits`, but returns an error, without writing anything, instead of panicking.
+            pub fn try_unset_bits(&mut self, bit_nbs: &[u8]) -> Result<(), String> {
+                self.try_mask_of(bit_nbs).map(|mask| self.bits &= !mask)
+            }
+
+            /// Like `set_from_ranges`, but returns an error, without writing anything, instead of panicking.
+            pub fn try_set_from_ranges<I: IntoIterator<Item = Range<u8>>>(
+                &mut self,
+                ranges: I,
+            ) -> Result<(), String> {
+                self.try_mask_of_ranges(ranges)
+                    .map(|mask| self.bits |= mask)
+            }
+
+            /// Like `unset_from_ranges`, but returns an error, without writing anything, instead of panicking.
+            pub fn try_unset_from_ranges<I: IntoIterator<Item = Range<u8>>>(
+                &mut self,
+                ranges: I,
+            ) -> Result<(), String> {
+                self.try_mask_of_ranges(ranges)
+                    .map(|mask| self.bits &= !mask)
+            }
+
+            /// # Safety
+            ///
+            /// `bit_nb` must be smaller than `nb_bits`; this is only checked in debug builds.
+            #[inline]
+            pub unsafe fn set_bit_unchecked(&mut self, bit_nb: u8) {
+                debug_assert!(bit_nb < self.nb_bits);
+                self.bits |= 1 << bit_nb;
+            }
+
+            /// # Safety
+            ///
+            /// `bit_nb` must be smaller than `nb_bits`; this is only checked in debug builds.
+            #[inline]
+            pub unsafe fn unset_bit_unchecked(&mut self, bit_nb: u8) {
+                debug_assert!(bit_nb < self.nb_bits);
+                self.bits &= !(1 << bit_nb);
+            }
+
+            /// # Safety
+            ///
+            /// `bit_nb` must be smaller than `nb_bits`; this is only checked in debug builds.
+            #[inline]
+            pub unsafe fn contains_unchecked(&self, bit_nb: u8) -> bool {
+                debug_assert!(bit_nb < self.nb_bits);
+                self.bits & (1 << bit_nb) != 0
+            }
+
             pub fn add(&mut self, bits: $bit_index_type) {
                 self.bits |= bits
             }
 
+            /// Renumbers the set bits densely to `0..nb_elements`, keeping their order, and returns
+            /// the mapping: entry `i` is the new bit number of old bit `i`, `None` if it was unset.
+            pub fn compact(&mut self) -> Vec<Option<u8>> {
+                let mut next = 0;
+                let mapping = (0..self.nb_bits)
+                    .map(|bit_nb| {
+                        if self.bits >> bit_nb & 1 == 1 {
+                            next += 1;
+                            Some(next - 1)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+                self.bits = Self::init(next);
+                mapping
+            }
+
+            /// Like `compact`, and also shrinks `nb_bits` to the number of elements.
+            pub fn compact_shrinking(&mut self) -> Vec<Option<u8>> {
+                let mapping = self.compact();
+                self.nb_bits = self.nb_elements();
+                mapping
+            }
+
+            /// Adds one to the bits read as an `nb_bits`-wide little-endian counter, wrapping to
+            /// zero past the all-ones value. Returns the carry out: whether it wrapped.
+            pub fn increment(&mut self) -> bool {
+                self.bits = self.bits.wrapping_add(1) & Self::init(self.nb_bits);
+                self.bits == 0
+            }
+
+            /// Subtracts one like `increment` adds it, wrapping from zero to all ones.
+            /// Returns the borrow out: whether it wrapped.
+            pub fn decrement(&mut self) -> bool {
+                let borrow = self.bits == 0;
+                self.bits = self.bits.wrapping_sub(1) & Self::init(self.nb_bits);
+                borrow
+            }
+
+            /// The incremented counter, or `None` if it would wrap.
+            pub fn checked_increment(&self) -> Option<Self> {
+                let mut res = *self;
+                if res.increment() {
+                    None
+                } else {
+                    Some(res)
+                }
+            }
+
+            /// The decremented counter, or `None` if it would wrap.
+            pub fn checked_decrement(&self) -> Option<Self> {
+                let mut res = *self;
+                if res.decrement() {
+                    None
+                } else {
+                    Some(res)
+                }
+            }
+
             pub fn absorb(&mut self, other: $bit_index_name) {
                 self.add(other.bits);
                 self.nb_bits = max(self.nb_bits, other.nb_bits);
             }
 
+            /// The elements of `self` that are not in `other`, with the `nb_bits` of `self`.
+            pub fn difference(&self, other: &Self) -> Self {
+                Self {
+                    bits: self.bits & !other.bits,
+                    nb_bits: self.nb_bits,
+                }
+            }
+
             #[inline]
             fn single_bit(&self, bit_nb: u8) -> $bit_index_type {
                 self.check_input(bit_nb);
                 1 << bit_nb
             }
 
+            fn mask_of(&self, bit_nbs: &[u8]) -> $bit_index_type {
+                self.try_mask_of(bit_nbs)
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            fn try_mask_of(&self, bit_nbs: &[u8]) -> Result<$bit_index_type, String> {
+                if let Some(&max_bit_nb) = bit_nbs.iter().max() {
+                    self.check_range(max_bit_nb)?;
+                }
+                Ok(bit_nbs.iter().fold(0, |mask, &bit_nb| mask | (1 << bit_nb)))
+            }
+
+            fn mask_of_ranges<I: IntoIterator<Item = Range<u8>>>(
+                &self,
+                ranges: I,
+            ) -> $bit_index_type {
+                self.try_mask_of_ranges(ranges)
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            fn try_mask_of_ranges<I: IntoIterator<Item = Range<u8>>>(
+                &self,
+                ranges: I,
+            ) -> Result<$bit_index_type, String> {
+                ranges
+                    .into_iter()
+                    .filter(|range| range.start < range.end)
+                    .try_fold(0, |mask, range| {
+                        self.check_range(range.end - 1)?;
+                        Ok(mask | (Self::init(range.end) ^ Self::init(range.start)))
+                    })
+            }
+
             // explicit check not necessary: handled by `single_bit`
             #[inline]
             fn all_but_single_bit(&self, bit_nb: u8) -> $bit_index_type {
@@ -192,12 +619,21 @@ macro_rules! impl_bit_index {
 
             #[inline]
             fn check_input(&self, i: u8) {
+                if let Err(e) = self.check_range(i) {
+                    panic!("{}", e)
+                }
+            }
+
+            #[inline]
+            fn check_range(&self, i: u8) -> Result<(), String> {
                 if i >= self.nb_bits {
-                    panic!(
+                    Err(format!(
                         "This {} can only handle inputs upto {}",
                         stringify!($bit_index_name),
                         self.nb_bits
-                    )
+                    ))
+                } else {
+                    Ok(())
                 }
             }
 
@@ -211,6 +647,125 @@ macro_rules! impl_bit_index {
             }
         }
 
+        impl BitIndex for $bit_index_name {
+            const SIZE: u8 = Self::SIZE;
+
+            fn new(nb_bits: u8) -> Result<Self, String> {
+                Self::new(nb_bits)
+            }
+
+            fn empty(nb_bits: u8) -> Result<Self, String> {
+                Self::empty(nb_bits)
+            }
+
+            fn nb_bits(&self) -> u8 {
+                self.nb_bits
+            }
+
+            fn to_u128(&self) -> u128 {
+                self.to_u128()
+            }
+
+            fn nb_elements(&self) -> u8 {
+                self.nb_elements()
+            }
+
+            fn is_empty(&self) -> bool {
+                self.is_empty()
+            }
+
+            fn clear(&mut self) {
+                self.clear()
+            }
+
+            fn restore(&mut self) {
+                self.restore()
+            }
+
+            fn contains(&self, bit_nb: u8) -> bool {
+                self.contains(bit_nb)
+            }
+
+            fn set_bit(&mut self, bit_nb: u8) {
+                self.set_bit(bit_nb)
+            }
+
+            fn unset_bit(&mut self, bit_nb: u8) {
+                self.unset_bit(bit_nb)
+            }
+
+            fn set_from_ranges<I: IntoIterator<Item = Range<u8>>>(&mut self, ranges: I) {
+                self.set_from_ranges(ranges)
+            }
+
+            fn unset_from_ranges<I: IntoIterator<Item = Range<u8>>>(&mut self, ranges: I) {
+                self.unset_from_ranges(ranges)
+            }
+
+            fn smallest(&self) -> Option<u8> {
+                self.smallest()
+            }
+
+            fn largest(&self) -> Option<u8> {
+                self.largest()
+            }
+
+            fn pop_smallest(&mut self) -> Option<u8> {
+                self.pop_smallest()
+            }
+
+            fn pop_largest(&mut self) -> Option<u8> {
+                self.pop_largest()
+            }
+
+            fn next_set_bit(&self, after: u8) -> Option<u8> {
+                self.next_set_bit(after)
+            }
+
+            fn next_set_bit_wrapping(&self, after: u8) -> Option<u8> {
+                self.next_set_bit_wrapping(after)
+            }
+
+            fn difference(&self, other: &Self) -> Self {
+                self.difference(other)
+            }
+        }
+
+        /// Takes the full width as `nb_bits`; fails if `value` does not fit.
+        impl TryFrom<u128> for $bit_index_name {
+            type Error = String;
+
+            fn try_from(value: u128) -> Result<Self, Self::Error> {
+                <$bit_index_type>::try_from(value)
+                    .map(|bits| Self {
+                        bits,
+                        nb_bits: Self::SIZE,
+                    })
+                    .map_err(|_| {
+                        format!(
+                            "This {} can only keep {} bits, {:#x} does not fit",
+                            stringify!($bit_index_name),
+                            Self::SIZE,
+                            value
+                        )
+                    })
+            }
+        }
+
+        /// Sends the raw integer, the binary rendering happens on the host.
+        #[cfg(feature = "defmt")]
+        impl defmt::Format for $bit_index_name {
+            fn format(&self, f: defmt::Formatter) {
+                defmt::write!(
+                    f,
+                    "{=str} {{ nb_bits: {=u8}, bits: {:b} }}",
+                    stringify!($bit_index_name),
+                    self.nb_bits,
+                    self.bits
+                )
+            }
+        }
+
         impl Debug for $bit_index_name {
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 writeln!(f, "{} {{", stringify!($bit_index_name))?;
@@ -243,6 +798,38 @@ mod tests {
         assert!(BitIndex64::new(69).is_err());
     }
 
+    #[test]
+    fn u128_conversions() {
+        let mut bi = BitIndex16::new(12).unwrap();
+        bi.unset_bit(0);
+        assert_eq!(0b111111111110, bi.to_u128());
+
+        let bi = BitIndex16::try_from(bi.to_u128()).unwrap();
+        assert_eq!(0b111111111110, bi.unwrap());
+        assert_eq!(16, bi.nb_bits());
+        assert!(BitIndex16::try_from(1 << 16).is_err());
+        assert!(BitIndex8::try_from(0xff).is_ok());
+        assert_eq!(
+            u128::MAX,
+            BitIndex128::try_from(u128::MAX).unwrap().to_u128()
+        );
+    }
+
+    #[test]
+    fn write_render() {
+        let mut bi = BitIndex8::new(6).unwrap();
+        bi.unset_bit(1);
+        let mut s = String::new();
+        bi.write_bits(&mut s).unwrap();
+        assert_eq!("111101", s);
+
+        let mut buf = [b'-'; 8];
+        assert_eq!(Ok(6), bi.render_into(&mut buf));
+        assert_eq!(b"111101--", &buf);
+        assert!(bi.render_into(&mut buf[..5]).is_err());
+        assert_eq!(Ok(0), BitIndex8::new(0).unwrap().render_into(&mut []));
+    }
+
     #[test]
     fn empty() {
         let mut bi = BitIndex8::empty(5).unwrap();
@@ -277,6 +864,193 @@ mod tests {
         assert_eq!(0b1110, bi.unwrap());
     }
 
+    #[test]
+    fn contains() {
+        let mut bi = BitIndex16::new(10).unwrap();
+        bi.unset_bit(4);
+        assert!(bi.contains(3));
+        assert!(!bi.contains(4));
+        unsafe {
+            bi.unset_bit_unchecked(3);
+            assert!(!bi.contains_unchecked(3));
+            bi.set_bit_unchecked(4);
+            assert!(bi.contains_unchecked(4));
+        }
+        assert_eq!(0b1111110111, bi.unwrap());
+    }
+
+    #[test]
+    fn try_methods() {
+        let mut bi = BitIndex8::empty(4).unwrap();
+        assert_eq!(Ok(()), bi.try_set_bit(3));
+        assert!(bi.try_set_bit(4).is_err());
+        assert_eq!(Ok(true), bi.try_contains(3));
+        assert_eq!(Ok(Some(3)), bi.try_get(0));
+        assert_eq!(Ok(None), bi.try_get(1));
+        assert!(bi.try_get(4).is_err());
+        assert_eq!(Ok(()), bi.try_unset_bit(3));
+        assert_eq!(Ok(false), bi.try_contains(3));
+        assert_eq!(
+            Err("This BitIndex8 can only handle inputs upto 4".to_string()),
+            bi.try_unset_bit(8)
+        );
+        assert!(bi.is_empty());
+
+        // the multi-bit methods write nothing when one input is out of range
+        assert!(bi.try_set_bits(&[0, 2, 4]).is_err());
+        assert!(bi.try_set_from_ranges(vec![0..2, 3..5]).is_err());
+        assert!(bi.is_empty());
+        assert_eq!(Ok(()), bi.try_set_bits(&[0, 2]));
+        assert_eq!(Ok(()), bi.try_set_from_ranges(Some(3..4)));
+        assert_eq!(0b1101, bi.unwrap());
+        assert_eq!(Ok(()), bi.try_unset_from_ranges(Some(0..1)));
+        assert!(bi.try_unset_bits(&[4]).is_err());
+        assert_eq!(Ok(()), bi.try_unset_bits(&[3]));
+        assert_eq!(0b100, bi.unwrap());
+
+        bi.set_bits(&[0, 3]);
+        assert_eq!(Ok(Some(3)), bi.try_get_from_high_end(0));
+        assert_eq!(Ok(Some(2)), bi.try_get_signed(-2));
+        assert!(bi.try_get_signed(-5).is_err());
+        assert!(bi.try_get_from_high_end(4).is_err());
+        assert_eq!(Ok(None), bi.try_pop(3));
+        assert!(bi.try_pop(4).is_err());
+        assert!(bi.try_pop_signed(4).is_err());
+        assert_eq!(Ok(Some(3)), bi.try_pop_signed(-1));
+        assert_eq!(Ok(Some(2)), bi.try_pop_from_high_end(0));
+        assert_eq!(Ok(Some(0)), bi.try_pop_from_low_end(0));
+        assert!(bi.is_empty());
+    }
+
+    #[test]
+    fn compact() {
+        let mut bi = BitIndex16::empty(10).unwrap();
+        bi.set_bits(&[1, 4, 5, 9]);
+        let mapping = bi.compact();
+        assert_eq!(Some(0), mapping[1]);
+        assert_eq!(Some(2), mapping[5]);
+        assert_eq!(Some(3), mapping[9]);
+        assert_eq!(None, mapping[8]);
+        assert_eq!(10, mapping.len());
+        assert_eq!((0b1111, 10), (bi.unwrap(), bi.nb_bits()));
+
+        // parallel arrays follow the mapping
+        let names = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"];
+        let mut bi = BitIndex16::empty(10).unwrap();
+        bi.set_bits(&[2, 7]);
+        let mapping = bi.compact_shrinking();
+        let mut compacted = vec![""; bi.nb_bits() as usize];
+        for (old, new) in mapping.iter().enumerate() {
+            if let Some(new) = new {
+                compacted[*new as usize] = names[old];
+            }
+        }
+        assert_eq!(vec!["c", "h"], compacted);
+        assert_eq!(BitIndex16::new(2).unwrap(), bi);
+
+        let mut full = BitIndex128::new(128).unwrap();
+        assert_eq!(Some(127), full.compact_shrinking()[127]);
+        assert_eq!(BitIndex128::new(128).unwrap(), full);
+    }
+
+    #[test]
+    fn counter() {
+        let mut bi = BitIndex8::empty(3).unwrap();
+        let states: Vec<_> = (0..9)
+            .map(|_| {
+                let carry = bi.increment();
+                (bi.unwrap(), carry)
+            })
+            .collect();
+        assert_eq!((1, false), states[0]);
+        assert_eq!((7, false), states[6]);
+        assert_eq!((0, true), states[7]);
+        assert_eq!((1, false), states[8]);
+
+        assert!(!bi.decrement());
+        assert!(bi.decrement());
+        assert_eq!(0b111, bi.unwrap());
+        assert_eq!(None, bi.checked_increment());
+        assert_eq!(Some(6), bi.checked_decrement().map(|bi| bi.unwrap()));
+        assert_eq!(None, BitIndex8::empty(3).unwrap().checked_decrement());
+
+        let mut full = BitIndex128::new(128).unwrap();
+        assert!(full.increment());
+        assert!(full.is_empty());
+        assert!(full.decrement());
+        assert_eq!(u128::MAX, full.unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn contains_panic() {
+        BitIndex16::new(10).unwrap().contains(10);
+    }
+
+    #[test]
+    fn set_unset_bits_batch() {
+        let mut bi = BitIndex32::empty(20).unwrap();
+        bi.set_bits(&[0, 3, 19, 3]);
+        assert_eq!(0b10000000000000001001, bi.unwrap());
+        bi.unset_bits(&[3, 19, 7]);
+        assert_eq!(0b1, bi.unwrap());
+        bi.set_bits(&[]);
+        assert_eq!(0b1, bi.unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_bits_panic() {
+        BitIndex8::empty(4).unwrap().set_bits(&[1, 4]);
+    }
+
+    #[test]
+    fn ranges() {
+        let mut bi = BitIndex16::empty(12).unwrap();
+        bi.set_from_ranges(vec![0..2, 5..9, 7..7, 11..12]);
+        assert_eq!(0b100111100011, bi.unwrap());
+        bi.unset_from_ranges(Some(1..6));
+        assert_eq!(0b100111000001, bi.unwrap());
+
+        let mut bi = BitIndex128::empty(128).unwrap();
+        bi.set_from_ranges(Some(0..128));
+        assert_eq!(u128::MAX, bi.unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn ranges_panic() {
+        BitIndex16::empty(12)
+            .unwrap()
+            .set_from_ranges(vec![0..2, 10..13]);
+    }
+
+    #[test]
+    fn find_pattern() {
+        let mut bi = BitIndex16::empty(12).unwrap();
+        bi.set_bits(&[0, 1, 3, 5, 6, 8, 11]);
+        let mut pattern = BitIndex16::empty(3).unwrap();
+        pattern.set_bits(&[0, 2]);
+        assert_eq!(vec![1, 3, 6], bi.find_pattern(pattern, 3));
+        assert_eq!(vec![0, 5], bi.find_pattern(BitIndex16::new(2).unwrap(), 2));
+        assert!(bi.find_pattern(pattern, 13).is_empty());
+        assert!(bi.find_pattern(pattern, 0).is_empty());
+    }
+
+    #[test]
+    fn find_zero_run() {
+        let mut bi = BitIndex16::empty(12).unwrap();
+        bi.set_bits(&[0, 3, 4, 9]);
+        assert_eq!(Some(1), bi.find_zero_run(1));
+        assert_eq!(Some(1), bi.find_zero_run(2));
+        assert_eq!(Some(5), bi.find_zero_run(3));
+        assert_eq!(Some(5), bi.find_zero_run(4));
+        assert_eq!(None, bi.find_zero_run(5));
+        assert_eq!(Some(0), bi.find_zero_run(0));
+        assert_eq!(None, BitIndex128::new(128).unwrap().find_zero_run(1));
+        assert_eq!(Some(0), BitIndex128::empty(128).unwrap().find_zero_run(128));
+    }
+
     #[test]
     #[should_panic]
     fn set_panic() {
@@ -363,6 +1137,21 @@ mod tests {
         assert_eq!(Some(6), bi.get_from_low_end(5));
     }
 
+    #[test]
+    fn next_set_bit() {
+        let mut bi = BitIndex8::empty(8).unwrap();
+        bi.set_bits(&[1, 4, 7]);
+        assert_eq!(Some(4), bi.next_set_bit(1));
+        assert_eq!(Some(7), bi.next_set_bit(4));
+        assert_eq!(None, bi.next_set_bit(7));
+        assert_eq!(Some(1), bi.next_set_bit_wrapping(7));
+        assert_eq!(Some(1), bi.next_set_bit_wrapping(200));
+        bi.unset_bits(&[4, 7]);
+        assert_eq!(Some(1), bi.next_set_bit_wrapping(1));
+        bi.clear();
+        assert_eq!(None, bi.next_set_bit_wrapping(3));
+    }
+
     #[test]
     fn get_largest() {
         let mut bi = BitIndex8::new(4).unwrap();
@@ -374,6 +1163,25 @@ mod tests {
         assert_eq!(None, bi.get(3));
     }
 
+    #[test]
+    fn get_signed() {
+        let mut bi = BitIndex8::new(6).unwrap();
+        bi.unset_bit(1);
+        assert_eq!(Some(0), bi.get_signed(0));
+        assert_eq!(Some(2), bi.get_signed(1));
+        assert_eq!(Some(5), bi.get_signed(-1));
+        assert_eq!(Some(4), bi.get_signed(-2));
+        assert_eq!(Some(0), bi.get_signed(-5));
+        assert_eq!(None, bi.get_signed(-6));
+        assert_eq!(Some(5), bi.pop_signed(-1));
+        assert_eq!(Some(4), bi.pop_signed(-1));
+        assert_eq!(3, bi.nb_elements());
+
+        let bi = BitIndex128::new(128).unwrap();
+        assert_eq!(Some(0), bi.get_signed(-128));
+        assert_eq!(Some(127), bi.get_signed(127));
+    }
+
     #[test]
     #[should_panic]
     fn get_panic() {
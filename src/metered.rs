@@ -0,0 +1,267 @@
+use crate::BitIndex;
+use std::cmp::max;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+// Trace-level event for a mutation, when the `tracing` feature is enabled.
+macro_rules! trace_mutation {
+    ($op:expr, bit_nb: $bit_nb:expr, $nb_elements:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            op = $op,
+            bit_nb = $bit_nb,
+            nb_elements = $nb_elements,
+            "BitIndex mutation"
+        );
+    };
+    ($op:expr, nb_changed: $nb_changed:expr, $nb_elements:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            op = $op,
+            nb_changed = $nb_changed,
+            nb_elements = $nb_elements,
+            "BitIndex mutation"
+        );
+    };
+}
+
+/// A snapshot of the counters of a `MeteredBitIndex`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct OccupancyMetrics {
+    /// The largest number of elements seen at once.
+    pub peak: u8,
+    /// The number of bits that went from unset to set.
+    pub nb_sets: u64,
+    /// The number of bits that went from set to unset.
+    pub nb_unsets: u64,
+    /// The total time during which all `nb_bits` bits were set.
+    pub time_at_full: Duration,
+}
+
+/// Wraps a `BitIndex` and records occupancy metrics on every mutation.
+///
+/// Only mutations that change a bit are counted: setting a set bit is not a set.
+/// With the `tracing` feature, every mutation also emits a trace-level event with
+/// the bit (or number of changed bits) and the resulting number of elements.
+///
+/// Equality and hashing only look at the wrapped index, not at the metrics.
+#[derive(Copy, Clone, Debug)]
+pub struct MeteredBitIndex<B> {
+    inner: B,
+    metrics: OccupancyMetrics,
+    full_since: Option<Instant>,
+}
+
+impl<B: BitIndex> MeteredBitIndex<B> {
+    pub fn new(inner: B) -> Self {
+        let mut metered = Self {
+            inner,
+            metrics: OccupancyMetrics::default(),
+            full_since: None,
+        };
+        metered.record(0, 0);
+        metered
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// The metrics so far, including the ongoing stretch at full occupancy.
+    pub fn metrics(&self) -> OccupancyMetrics {
+        let mut metrics = self.metrics;
+        if let Some(since) = self.full_since {
+            metrics.time_at_full += since.elapsed();
+        }
+        metrics
+    }
+
+    /// Restarts the metrics from the current state.
+    pub fn reset_metrics(&mut self) {
+        self.metrics = OccupancyMetrics::default();
+        self.full_since = None;
+        self.record(0, 0);
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.inner.nb_elements() == self.inner.nb_bits()
+    }
+
+    pub fn contains(&self, bit_nb: u8) -> bool {
+        self.inner.contains(bit_nb)
+    }
+
+    pub fn set_bit(&mut self, bit_nb: u8) {
+        if !self.inner.contains(bit_nb) {
+            self.inner.set_bit(bit_nb);
+            self.record(1, 0);
+        }
+        trace_mutation!("set_bit", bit_nb: bit_nb, self.inner.nb_elements());
+    }
+
+    pub fn unset_bit(&mut self, bit_nb: u8) {
+        if self.inner.contains(bit_nb) {
+            self.inner.unset_bit(bit_nb);
+            self.record(0, 1);
+        }
+        trace_mutation!("unset_bit", bit_nb: bit_nb, self.inner.nb_elements());
+    }
+
+    pub fn pop_smallest(&mut self) -> Option<u8> {
+        let res = self.inner.pop_smallest();
+        if res.is_some() {
+            self.record(0, 1);
+        }
+        trace_mutation!("pop_smallest", bit_nb: tracing::field::debug(res), self.inner.nb_elements());
+        res
+    }
+
+    pub fn pop_largest(&mut self) -> Option<u8> {
+        let res = self.inner.pop_largest();
+        if res.is_some() {
+            self.record(0, 1);
+        }
+        trace_mutation!("pop_largest", bit_nb: tracing::field::debug(res), self.inner.nb_elements());
+        res
+    }
+
+    pub fn clear(&mut self) {
+        let nb_unsets = self.inner.nb_elements();
+        self.inner.clear();
+        self.record(0, nb_unsets);
+        trace_mutation!("clear", nb_changed: nb_unsets, self.inner.nb_elements());
+    }
+
+    pub fn restore(&mut self) {
+        let nb_sets = self.inner.nb_bits() - self.inner.nb_elements();
+        self.inner.restore();
+        self.record(nb_sets, 0);
+        trace_mutation!("restore", nb_changed: nb_sets, self.inner.nb_elements());
+    }
+
+    fn record(&mut self, nb_sets: u8, nb_unsets: u8) {
+        self.metrics.nb_sets += nb_sets as u64;
+        self.metrics.nb_unsets += nb_unsets as u64;
+        self.metrics.peak = max(self.metrics.peak, self.inner.nb_elements());
+        match (self.is_full(), self.full_since) {
+            (true, None) => self.full_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                self.metrics.time_at_full += since.elapsed();
+                self.full_since = None;
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<B: BitIndex> From<B> for MeteredBitIndex<B> {
+    fn from(inner: B) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<B: PartialEq> PartialEq for MeteredBitIndex<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<B: Eq> Eq for MeteredBitIndex<B> {}
+
+impl<B: Hash> Hash for MeteredBitIndex<B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitIndex8;
+
+    #[test]
+    fn counters() {
+        let mut metered = MeteredBitIndex::new(BitIndex8::empty(4).unwrap());
+        metered.set_bit(0);
+        metered.set_bit(0);
+        metered.set_bit(2);
+        metered.unset_bit(0);
+        metered.unset_bit(0);
+        assert_eq!(Some(2), metered.pop_largest());
+        metered.restore();
+        metered.clear();
+
+        let metrics = metered.metrics();
+        assert_eq!(4, metrics.peak);
+        assert_eq!(2 + 4, metrics.nb_sets);
+        assert_eq!(2 + 4, metrics.nb_unsets);
+        assert!(!metered.is_full());
+
+        metered.reset_metrics();
+        assert_eq!(OccupancyMetrics::default(), metered.metrics());
+    }
+
+    #[test]
+    fn time_at_full() {
+        let mut metered = MeteredBitIndex::new(BitIndex8::new(3).unwrap());
+        assert!(metered.is_full());
+        assert_eq!(3, metered.metrics().peak);
+        std::thread::sleep(Duration::from_millis(2));
+        metered.pop_smallest();
+        let at_full = metered.metrics().time_at_full;
+        assert!(at_full >= Duration::from_millis(2));
+        assert_eq!(at_full, metered.metrics().time_at_full);
+
+        // a fresh wrapper with the same contents, but other metrics and timestamps
+        let fresh = MeteredBitIndex::new(*metered.inner());
+        assert_eq!(fresh, metered);
+        let hash = |m: &MeteredBitIndex<BitIndex8>| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            m.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&fresh), hash(&metered));
+        metered.restore();
+        assert_ne!(fresh, metered);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn trace_events() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+
+        struct CountEvents(Arc<AtomicUsize>);
+
+        impl tracing::Subscriber for CountEvents {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        tracing::subscriber::with_default(CountEvents(count.clone()), || {
+            let mut metered = MeteredBitIndex::new(BitIndex8::new(4).unwrap());
+            metered.unset_bit(1);
+            metered.pop_largest();
+            metered.clear();
+        });
+        assert_eq!(3, count.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,126 @@
+//! Applying masks to memory-mapped registers.
+
+use crate::{BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+use std::ptr;
+
+macro_rules! impl_mmio_bits {
+    ($mmio_name:ident, $bit_index_name:ident, $register_type:ty) => {
+        /// A volatile view on a register, driven with `BitIndex` masks.
+        ///
+        /// `set` and `clear` use dedicated set/clear registers when the view has them,
+        /// and a read-modify-write of the register otherwise. `toggle` is always a read-modify-write.
+        #[derive(Debug)]
+        pub struct $mmio_name {
+            reg: *mut $register_type,
+            set_clear_regs: Option<(*mut $register_type, *mut $register_type)>,
+        }
+
+        impl $mmio_name {
+            /// # Safety
+            ///
+            /// `reg` must be valid for volatile reads and writes as long as the view is used.
+            pub unsafe fn new(reg: *mut $register_type) -> Self {
+                Self {
+                    reg,
+                    set_clear_regs: None,
+                }
+            }
+
+            /// # Safety
+            ///
+            /// All three registers must be valid for volatile accesses as long as the view is used,
+            /// and writing ones to `set_reg`/`clear_reg` must set/clear those bits of `reg` in hardware.
+            pub unsafe fn with_set_clear(
+                reg: *mut $register_type,
+                set_reg: *mut $register_type,
+                clear_reg: *mut $register_type,
+            ) -> Self {
+                Self {
+                    reg,
+                    set_clear_regs: Some((set_reg, clear_reg)),
+                }
+            }
+
+            pub fn read(&self) -> $bit_index_name {
+                $bit_index_name {
+                    // SAFETY: `new` and `with_set_clear` require `reg` to be valid for volatile reads
+                    bits: unsafe { ptr::read_volatile(self.reg) },
+                    nb_bits: $bit_index_name::SIZE,
+                }
+            }
+
+            pub fn write(&mut self, value: $bit_index_name) {
+                // SAFETY: `new` and `with_set_clear` require `reg` to be valid for volatile writes
+                unsafe { ptr::write_volatile(self.reg, value.bits) }
+            }
+
+            pub fn set(&mut self, mask: $bit_index_name) {
+                match self.set_clear_regs {
+                    Some((set_reg, _)) => {
+                        // SAFETY: `with_set_clear` requires `set_reg` to be valid for volatile writes
+                        unsafe { ptr::write_volatile(set_reg, mask.bits) }
+                    }
+                    None => self.modify(|bits| bits | mask.bits),
+                }
+            }
+
+            pub fn clear(&mut self, mask: $bit_index_name) {
+                match self.set_clear_regs {
+                    Some((_, clear_reg)) => {
+                        // SAFETY: `with_set_clear` requires `clear_reg` to be valid for volatile writes
+                        unsafe { ptr::write_volatile(clear_reg, mask.bits) }
+                    }
+                    None => self.modify(|bits| bits & !mask.bits),
+                }
+            }
+
+            pub fn toggle(&mut self, mask: $bit_index_name) {
+                self.modify(|bits| bits ^ mask.bits);
+            }
+
+            fn modify(&mut self, f: impl FnOnce($register_type) -> $register_type) {
+                // SAFETY: `new` and `with_set_clear` require `reg` to be valid for volatile reads
+                // and writes
+                unsafe { ptr::write_volatile(self.reg, f(ptr::read_volatile(self.reg))) }
+            }
+        }
+    };
+}
+
+impl_mmio_bits!(MmioBits8, BitIndex8, u8);
+impl_mmio_bits!(MmioBits16, BitIndex16, u16);
+impl_mmio_bits!(MmioBits32, BitIndex32, u32);
+impl_mmio_bits!(MmioBits64, BitIndex64, u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_modify_write() {
+        let mut reg: u32 = 0b1001;
+        let mut mmio = unsafe { MmioBits32::new(&mut reg) };
+        let mut mask = BitIndex32::empty(32).unwrap();
+        mask.set_bits(&[1, 3]);
+
+        mmio.set(mask);
+        assert_eq!(0b1011, mmio.read().unwrap());
+        mmio.clear(mask);
+        assert_eq!(0b0001, mmio.read().unwrap());
+        mmio.toggle(mask);
+        assert_eq!(0b1011, mmio.read().unwrap());
+        mmio.write(BitIndex32::new(4).unwrap());
+        assert_eq!(0b1111, reg);
+    }
+
+    #[test]
+    fn set_clear_registers() {
+        let (mut reg, mut set_reg, mut clear_reg): (u8, u8, u8) = (0b1, 0, 0);
+        let mut mmio = unsafe { MmioBits8::with_set_clear(&mut reg, &mut set_reg, &mut clear_reg) };
+        mmio.set(BitIndex8::new(2).unwrap());
+        mmio.clear(BitIndex8::new(1).unwrap());
+        assert_eq!(0b1, mmio.read().unwrap());
+        mmio.toggle(BitIndex8::new(3).unwrap());
+        assert_eq!((0b110, 0b11, 0b1), (reg, set_reg, clear_reg));
+    }
+}
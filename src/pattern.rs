@@ -0,0 +1,80 @@
+use crate::BitIndex;
+
+/// A ternary (TCAM-style) pattern: a value and a care mask.
+///
+/// An index matches when it agrees with `value` on every bit set in `care`;
+/// bits outside `care` are "don't care".
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BitPattern<B> {
+    value: B,
+    care: B,
+}
+
+impl<B: BitIndex> BitPattern<B> {
+    pub fn new(value: B, care: B) -> Self {
+        Self { value, care }
+    }
+
+    /// A pattern caring about all `nb_bits` of `value`.
+    pub fn exact(value: B) -> Self {
+        let mut care = value;
+        care.restore();
+        Self { value, care }
+    }
+
+    pub fn value(&self) -> &B {
+        &self.value
+    }
+
+    pub fn care(&self) -> &B {
+        &self.care
+    }
+
+    /// The number of bits the pattern cares about.
+    pub fn specificity(&self) -> u8 {
+        self.care.nb_elements()
+    }
+
+    pub fn matches(&self, bi: &B) -> bool {
+        (bi.to_u128() ^ self.value.to_u128()) & self.care.to_u128() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitIndex8;
+
+    #[test]
+    fn matches() {
+        // 1x0x
+        let mut value = BitIndex8::empty(4).unwrap();
+        value.set_bit(3);
+        let mut care = BitIndex8::empty(4).unwrap();
+        care.set_bits(&[1, 3]);
+        let pattern = BitPattern::new(value, care);
+        assert_eq!(2, pattern.specificity());
+
+        let mut bi = BitIndex8::empty(4).unwrap();
+        bi.set_bits(&[0, 2, 3]);
+        assert!(pattern.matches(&bi));
+        bi.set_bit(1);
+        assert!(!pattern.matches(&bi));
+        bi.unset_bits(&[0, 1, 2]);
+        assert!(pattern.matches(&bi));
+        bi.unset_bit(3);
+        assert!(!pattern.matches(&bi));
+    }
+
+    #[test]
+    fn exact() {
+        let mut value = BitIndex8::empty(4).unwrap();
+        value.set_bit(2);
+        let pattern = BitPattern::exact(value);
+        assert_eq!(4, pattern.specificity());
+        assert!(pattern.matches(&value));
+        let mut other = value;
+        other.set_bit(0);
+        assert!(!pattern.matches(&other));
+    }
+}
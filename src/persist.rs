@@ -0,0 +1,234 @@
+//! Self-describing binary format for a single `BitIndex`.
+//!
+//! | bytes       | content                                           |
+//! |-------------|---------------------------------------------------|
+//! | 4           | magic `BIDX`                                      |
+//! | 1           | format version                                    |
+//! | 1           | width tag: bit size of the saved type (8..=128)   |
+//! | 1           | `nb_bits`                                         |
+//! | width / 8   | bits, little-endian                               |
+//! | 4           | CRC-32 of all preceding bytes, little-endian      |
+//!
+//! A file can be loaded into any width that is large enough to hold its `nb_bits` and bits.
+
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"BIDX";
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 7;
+
+macro_rules! impl_persist {
+    ($bit_index_name:ident, $bit_index_type:ty) => {
+        impl $bit_index_name {
+            /// Bits at or above `nb_bits`, as left by `add` or `absorb`, represent nothing and
+            /// are not written.
+            pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+                let mut buf = Vec::with_capacity(HEADER_LEN + Self::SIZE as usize / 8 + 4);
+                buf.extend_from_slice(MAGIC);
+                buf.push(FORMAT_VERSION);
+                buf.push(Self::SIZE);
+                buf.push(self.nb_bits);
+                buf.extend_from_slice(&(self.bits & Self::init(self.nb_bits)).to_le_bytes());
+                let checksum = crc32(&buf);
+                buf.extend_from_slice(&checksum.to_le_bytes());
+                writer.write_all(&buf)
+            }
+
+            pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+                let mut buf = vec![0; HEADER_LEN];
+                reader.read_exact(&mut buf)?;
+                if &buf[..4] != MAGIC {
+                    return Err(invalid_data("not a BitIndex file".to_string()));
+                }
+                if buf[4] != FORMAT_VERSION {
+                    return Err(invalid_data(format!(
+                        "unsupported format version {}",
+                        buf[4]
+                    )));
+                }
+                let width = buf[5];
+                if ![8, 16, 32, 64, 128].contains(&width) {
+                    return Err(invalid_data(format!("invalid width tag {}", width)));
+                }
+                let nb_bits = buf[6];
+                if nb_bits > width {
+                    return Err(invalid_data(format!(
+                        "{} bits do not fit in a {}-bit index",
+                        nb_bits, width
+                    )));
+                }
+
+                buf.resize(HEADER_LEN + width as usize / 8 + 4, 0);
+                reader.read_exact(&mut buf[HEADER_LEN..])?;
+                let (data, checksum) = buf.split_at(buf.len() - 4);
+                if crc32(data)
+                    != u32::from_le_bytes([checksum[0], checksum[1], checksum[2], checksum[3]])
+                {
+                    return Err(invalid_data("checksum mismatch".to_string()));
+                }
+
+                let mut bits = [0; 16];
+                bits[..data.len() - HEADER_LEN].copy_from_slice(&data[HEADER_LEN..]);
+                let bits = u128::from_le_bytes(bits);
+                if nb_bits < 128 && bits >> nb_bits != 0 {
+                    return Err(invalid_data(format!(
+                        "bits set at or above nb_bits {}",
+                        nb_bits
+                    )));
+                }
+                if nb_bits > Self::SIZE || bits > <$bit_index_type>::MAX as u128 {
+                    return Err(invalid_data(format!(
+                        "this {}-bit index does not fit in a {}",
+                        width,
+                        stringify!($bit_index_name)
+                    )));
+                }
+                Ok(Self {
+                    bits: bits as $bit_index_type,
+                    nb_bits,
+                })
+            }
+
+            pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+                let mut writer = BufWriter::new(File::create(path)?);
+                self.write_to(&mut writer)?;
+                writer.flush()
+            }
+
+            pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+                Self::read_from(BufReader::new(File::open(path)?))
+            }
+        }
+    };
+}
+
+impl_persist!(BitIndex8, u8);
+impl_persist!(BitIndex16, u16);
+impl_persist!(BitIndex32, u32);
+impl_persist!(BitIndex64, u64);
+impl_persist!(BitIndex128, u128);
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// CRC-32 (IEEE), bitwise: the payloads are a handful of bytes.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let mut bi = BitIndex64::new(44).unwrap();
+        bi.unset_bit(3);
+        let mut buf = Vec::new();
+        bi.write_to(&mut buf).unwrap();
+        assert_eq!(b"BIDX", &buf[..4]);
+        assert_eq!(7 + 8 + 4, buf.len());
+        assert_eq!(bi, BitIndex64::read_from(&buf[..]).unwrap());
+
+        // widening is fine, narrowing only when the content fits
+        let wide = BitIndex128::read_from(&buf[..]).unwrap();
+        assert_eq!((44, 43), (wide.nb_bits, wide.nb_elements()));
+        assert!(BitIndex32::read_from(&buf[..]).is_err());
+
+        let mut buf = Vec::new();
+        BitIndex128::new(5).unwrap().write_to(&mut buf).unwrap();
+        assert_eq!(0b11111, BitIndex8::read_from(&buf[..]).unwrap().unwrap());
+    }
+
+    #[test]
+    fn corrupt() {
+        let mut buf = Vec::new();
+        BitIndex16::new(12).unwrap().write_to(&mut buf).unwrap();
+
+        let mut flipped = buf.clone();
+        flipped[8] ^= 1;
+        assert!(BitIndex16::read_from(&flipped[..]).is_err());
+
+        let mut future = buf.clone();
+        future[4] = FORMAT_VERSION + 1;
+        assert!(BitIndex16::read_from(&future[..]).is_err());
+
+        assert!(BitIndex16::read_from(&buf[..buf.len() - 1]).is_err());
+        assert!(BitIndex16::read_from(&b"nope nope nope"[..]).is_err());
+    }
+
+    // rewrites the header and bits of `buf`, with a valid checksum
+    fn forge(buf: &mut [u8], nb_bits: u8, bits: u8) {
+        buf[6] = nb_bits;
+        buf[7] = bits;
+        let end = buf.len() - 4;
+        let checksum = crc32(&buf[..end]);
+        buf[end..].copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    #[test]
+    fn bits_above_nb_bits() {
+        let mut buf = Vec::new();
+        BitIndex8::new(4).unwrap().write_to(&mut buf).unwrap();
+        forge(&mut buf, 4, 0xF0);
+        let err = BitIndex8::read_from(&buf[..]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert!(BitIndex64::read_from(&buf[..]).is_err());
+
+        forge(&mut buf, 8, 0xF0);
+        assert_eq!(0xF0, BitIndex8::read_from(&buf[..]).unwrap().unwrap());
+    }
+
+    #[test]
+    fn added_bits_above_nb_bits() {
+        let mut bi = BitIndex8::new(4).unwrap();
+        bi.add(0xF0);
+        let mut buf = Vec::new();
+        bi.write_to(&mut buf).unwrap();
+        assert_eq!(
+            BitIndex8::new(4).unwrap(),
+            BitIndex8::read_from(&buf[..]).unwrap()
+        );
+    }
+
+    #[test]
+    fn nb_bits_above_width() {
+        let mut buf = Vec::new();
+        BitIndex8::new(4).unwrap().write_to(&mut buf).unwrap();
+        forge(&mut buf, 100, 0);
+        let err = BitIndex128::read_from(&buf[..]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn save_load() {
+        let path = std::env::temp_dir().join(format!(
+            "bit-index-persist-test-{}.bidx",
+            std::process::id()
+        ));
+        let bi = BitIndex32::new(20).unwrap();
+        bi.save(&path).unwrap();
+        assert_eq!(bi, BitIndex32::load(&path).unwrap());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+    }
+}
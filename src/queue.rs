@@ -0,0 +1,125 @@
+use crate::BitIndex;
+
+/// A double-ended priority queue of small integer keys, backed by a `BitIndex`.
+///
+/// Keys are in `0..capacity` and each key is queued at most once: pushing a key that is already
+/// queued does nothing. Every operation is a constant number of bit instructions, which makes it
+/// a cheap alternative to a `BinaryHeap` when keys are this small.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BitQueue<B> {
+    keys: B,
+}
+
+impl<B: BitIndex> BitQueue<B> {
+    /// A queue accepting every key the width can keep.
+    pub fn new() -> Self {
+        Self {
+            keys: B::empty(B::SIZE).unwrap(),
+        }
+    }
+
+    pub fn with_capacity(capacity: u8) -> Result<Self, String> {
+        B::empty(capacity).map(|keys| Self { keys })
+    }
+
+    pub fn capacity(&self) -> u8 {
+        self.keys.nb_bits()
+    }
+
+    pub fn len(&self) -> u8 {
+        self.keys.nb_elements()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    pub fn contains(&self, key: u8) -> bool {
+        key < self.capacity() && self.keys.contains(key)
+    }
+
+    pub fn push(&mut self, key: u8) -> Result<(), String> {
+        if key >= self.capacity() {
+            return Err(format!(
+                "This BitQueue can only keep keys below {}, not {}",
+                self.capacity(),
+                key
+            ));
+        }
+        self.keys.set_bit(key);
+        Ok(())
+    }
+
+    /// Removes `key` from the queue, returns whether it was queued.
+    pub fn remove(&mut self, key: u8) -> bool {
+        let res = self.contains(key);
+        if res {
+            self.keys.unset_bit(key);
+        }
+        res
+    }
+
+    pub fn peek_min(&self) -> Option<u8> {
+        self.keys.smallest()
+    }
+
+    pub fn peek_max(&self) -> Option<u8> {
+        self.keys.largest()
+    }
+
+    pub fn pop_min(&mut self) -> Option<u8> {
+        self.keys.pop_smallest()
+    }
+
+    pub fn pop_max(&mut self) -> Option<u8> {
+        self.keys.pop_largest()
+    }
+}
+
+impl<B: BitIndex> Default for BitQueue<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitIndex128, BitIndex8};
+
+    #[test]
+    fn push_pop() {
+        let mut queue = BitQueue::<BitIndex8>::new();
+        assert_eq!(8, queue.capacity());
+        assert_eq!(None, queue.peek_min());
+        queue.push(5).unwrap();
+        queue.push(2).unwrap();
+        queue.push(7).unwrap();
+        queue.push(2).unwrap();
+        assert_eq!(3, queue.len());
+        assert_eq!(Some(2), queue.peek_min());
+        assert_eq!(Some(7), queue.peek_max());
+        assert_eq!(Some(7), queue.pop_max());
+        assert_eq!(Some(2), queue.pop_min());
+        assert_eq!(Some(5), queue.pop_min());
+        assert_eq!(None, queue.pop_max());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn capacity() {
+        assert!(BitQueue::<BitIndex8>::with_capacity(9).is_err());
+        let mut queue = BitQueue::<BitIndex128>::with_capacity(100).unwrap();
+        assert!(queue.push(100).is_err());
+        queue.push(99).unwrap();
+        assert!(queue.contains(99));
+        assert!(!queue.contains(120));
+        assert!(queue.remove(99));
+        assert!(!queue.remove(99));
+        assert!(!queue.remove(120));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::BitIndex;
+
+/// A cursor for fair scheduling over the set bits of a `BitIndex`.
+///
+/// Every call to `next` picks the first candidate after the previously picked one,
+/// wrapping around to the smallest candidate past the end.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct RoundRobin {
+    last: Option<u8>,
+}
+
+impl RoundRobin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The previously picked bit.
+    pub fn last(&self) -> Option<u8> {
+        self.last
+    }
+
+    /// Forgets the previous pick, so the next one starts from the smallest candidate.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+
+    pub fn next<B: BitIndex>(&mut self, candidates: &B) -> Option<u8> {
+        let res = match self.last {
+            Some(last) => candidates.next_set_bit_wrapping(last),
+            None => candidates.smallest(),
+        };
+        if res.is_some() {
+            self.last = res;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitIndex16;
+
+    #[test]
+    fn wraps_around() {
+        let mut candidates = BitIndex16::empty(10).unwrap();
+        candidates.set_bits(&[2, 5, 9]);
+        let mut rr = RoundRobin::new();
+        let picks: Vec<_> = (0..5).filter_map(|_| rr.next(&candidates)).collect();
+        assert_eq!(vec![2, 5, 9, 2, 5], picks);
+
+        // the cursor survives changes to the candidates
+        candidates.unset_bit(9);
+        candidates.set_bit(7);
+        assert_eq!(Some(7), rr.next(&candidates));
+        assert_eq!(Some(2), rr.next(&candidates));
+
+        candidates.clear();
+        assert_eq!(None, rr.next(&candidates));
+        assert_eq!(Some(2), rr.last());
+        rr.reset();
+        assert_eq!(None, rr.last());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{BitIndex128, BitIndex16, BitIndex32, BitIndex64, BitIndex8};
+
+/// Fragmentation metrics of the set bits of an index.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Stats {
+    /// The fraction of the `nb_bits` bits that are set.
+    pub density: f64,
+    /// The number of maximal runs of consecutive set bits.
+    pub nb_runs: u8,
+    /// The average number of unset bits between two consecutive set bits, `None` below two elements.
+    pub mean_gap: Option<f64>,
+    /// The fraction of consecutive set bits that are adjacent: 1 for a single run,
+    /// 0 when every set bit is isolated, `None` below two elements.
+    pub clustering: Option<f64>,
+}
+
+macro_rules! impl_stats {
+    ($bit_index_name:ident) => {
+        impl $bit_index_name {
+            pub fn stats(&self) -> Stats {
+                stats_of(self.bits as u128, self.nb_bits)
+            }
+        }
+    };
+}
+
+impl_stats!(BitIndex8);
+impl_stats!(BitIndex16);
+impl_stats!(BitIndex32);
+impl_stats!(BitIndex64);
+impl_stats!(BitIndex128);
+
+fn stats_of(bits: u128, nb_bits: u8) -> Stats {
+    let nb_elements = bits.count_ones();
+    let nb_runs = (bits & !(bits << 1)).count_ones();
+    let (mean_gap, clustering) = if nb_elements < 2 {
+        (None, None)
+    } else {
+        let span = 128 - bits.leading_zeros() - bits.trailing_zeros();
+        let nb_pairs = (nb_elements - 1) as f64;
+        (
+            Some((span - nb_elements) as f64 / nb_pairs),
+            Some((nb_elements - nb_runs) as f64 / nb_pairs),
+        )
+    };
+    Stats {
+        density: if nb_bits == 0 {
+            0.0
+        } else {
+            nb_elements as f64 / nb_bits as f64
+        },
+        nb_runs: nb_runs as u8,
+        mean_gap,
+        clustering,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragmented() {
+        let mut bi = BitIndex16::empty(16).unwrap();
+        bi.set_bits(&[0, 1, 2, 6, 10, 11]);
+        let stats = bi.stats();
+        assert_eq!(6.0 / 16.0, stats.density);
+        assert_eq!(3, stats.nb_runs);
+        // gaps of 0, 0, 3, 3, 0 unset bits
+        assert_eq!(Some(6.0 / 5.0), stats.mean_gap);
+        assert_eq!(Some(3.0 / 5.0), stats.clustering);
+    }
+
+    #[test]
+    fn extremes() {
+        let stats = BitIndex128::new(128).unwrap().stats();
+        assert_eq!((1.0, 1), (stats.density, stats.nb_runs));
+        assert_eq!((Some(0.0), Some(1.0)), (stats.mean_gap, stats.clustering));
+
+        let mut bi = BitIndex8::empty(8).unwrap();
+        bi.set_bits(&[1, 3, 5]);
+        assert_eq!(Some(0.0), bi.stats().clustering);
+        bi.clear();
+        let stats = bi.stats();
+        assert_eq!(
+            (0.0, 0, None),
+            (stats.density, stats.nb_runs, stats.mean_gap)
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Bit-granular reading and writing over byte streams.
+//!
+//! Bits are packed little-endian, like the indexes themselves: the first bit written is the
+//! least significant bit of the first byte, and multi-bit values are written least significant
+//! bit first. Since `Vec<u8>` is `Write` and `&[u8]` is `Read`, in-memory buffers work directly.
+
+mod codes;
+
+use crate::BitIndex;
+use std::cmp::min;
+use std::io::{self, Read, Write};
+
+/// Writes bits to an underlying writer a byte at a time.
+///
+/// A partial last byte stays pending until `align` or `into_inner` pads it with zeros; like
+/// `BufWriter`, dropping the writer writes it too, but ignores any error doing so.
+#[derive(Debug)]
+pub struct BitWriter<W: Write> {
+    // only `None` after `into_inner`
+    inner: Option<W>,
+    pending: u8,
+    nb_pending: u8,
+}
+
+impl<W: Write> BitWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: Some(inner),
+            pending: 0,
+            nb_pending: 0,
+        }
+    }
+
+    pub fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.write_bits(bit as u128, 1)
+    }
+
+    /// Writes the `width` least significant bits of `value`; higher bits are ignored.
+    pub fn write_bits(&mut self, value: u128, width: u8) -> io::Result<()> {
+        check_width(width)?;
+        let mut value = if width == 128 {
+            value
+        } else {
+            value & ((1 << width) - 1)
+        };
+        let mut remaining = width;
+        while remaining > 0 {
+            let take = min(8 - self.nb_pending, remaining);
+            self.pending |= ((value & ((1 << take) - 1)) as u8) << self.nb_pending;
+            self.nb_pending += take;
+            value >>= take;
+            remaining -= take;
+            if self.nb_pending == 8 {
+                self.write_pending()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the `nb_bits` bits of `bi`.
+    pub fn write_index<B: BitIndex>(&mut self, bi: &B) -> io::Result<()> {
+        (0..bi.nb_bits()).try_for_each(|bit_nb| self.write_bit(bi.contains(bit_nb)))
+    }
+
+    /// Pads the current byte with zeros and writes it, so the next write starts on a byte
+    /// boundary. Does nothing if already aligned.
+    pub fn align(&mut self) -> io::Result<()> {
+        if self.nb_pending > 0 {
+            self.write_pending()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the underlying writer. A partial byte stays pending, see `align`.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner_mut().flush()
+    }
+
+    /// Aligns, flushes and returns the underlying writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.align()?;
+        self.flush()?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    fn write_pending(&mut self) -> io::Result<()> {
+        let byte = self.pending;
+        self.inner_mut().write_all(&[byte])?;
+        self.pending = 0;
+        self.nb_pending = 0;
+        Ok(())
+    }
+
+    fn inner_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap()
+    }
+}
+
+impl<W: Write> Drop for BitWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.align();
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BitReader<R: Read> {
+    inner: R,
+    current: u8,
+    nb_left: u8,
+}
+
+impl<R: Read> BitReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            current: 0,
+            nb_left: 0,
+        }
+    }
+
+    pub fn read_bit(&mut self) -> io::Result<bool> {
+        self.read_bits(1).map(|bit| bit == 1)
+    }
+
+    pub fn read_bits(&mut self, width: u8) -> io::Result<u128> {
+        check_width(width)?;
+        let mut value = 0;
+        let mut nb_read = 0;
+        while nb_read < width {
+            if self.nb_left == 0 {
+                let mut byte = [0];
+                self.inner.read_exact(&mut byte)?;
+                self.current = byte[0];
+                self.nb_left = 8;
+            }
+            let take = min(self.nb_left, width - nb_read);
+            let chunk = self.current as u128 & ((1 << take) - 1);
+            value |= chunk << nb_read;
+            self.current = self.current.checked_shr(take as u32).unwrap_or(0);
+            self.nb_left -= take;
+            nb_read += take;
+        }
+        Ok(value)
+    }
+
+    /// Reads `nb_bits` bits into a `BitIndex` of that size.
+    pub fn read_index<B: BitIndex>(&mut self, nb_bits: u8) -> io::Result<B> {
+        let mut bi =
+            B::empty(nb_bits).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        for bit_nb in 0..nb_bits {
+            if self.read_bit()? {
+                bi.set_bit(bit_nb);
+            }
+        }
+        Ok(bi)
+    }
+
+    /// Skips the rest of the current byte, so the next read starts on a byte boundary.
+    pub fn align(&mut self) {
+        self.current = 0;
+        self.nb_left = 0;
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+fn check_width(width: u8) -> io::Result<()> {
+    if width > 128 {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Can only transfer upto 128 bits at once, not {}", width),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitIndex16, BitIndex8};
+
+    #[test]
+    fn write_read() {
+        let mut writer = BitWriter::new(Vec::new());
+        writer.write_bit(true).unwrap();
+        writer.write_bits(0b101, 3).unwrap();
+        writer.write_bits(0xABCD, 16).unwrap();
+        writer.write_bits(u128::MAX, 128).unwrap();
+        writer.write_bits(0b11111, 2).unwrap();
+        let buf = writer.into_inner().unwrap();
+        assert_eq!(0b1101_1011, buf[0]);
+        assert_eq!(19, buf.len());
+
+        let mut reader = BitReader::new(&buf[..]);
+        assert!(reader.read_bit().unwrap());
+        assert_eq!(0b101, reader.read_bits(3).unwrap());
+        assert_eq!(0xABCD, reader.read_bits(16).unwrap());
+        assert_eq!(u128::MAX, reader.read_bits(128).unwrap());
+        assert_eq!(0b11, reader.read_bits(2).unwrap());
+        assert_eq!(0, reader.read_bits(2).unwrap());
+        assert!(reader.read_bits(8).is_err());
+        assert!(reader.read_bits(129).is_err());
+    }
+
+    #[test]
+    fn indexes() {
+        let mut bi = BitIndex16::new(11).unwrap();
+        bi.unset_bit(3);
+        let mut writer = BitWriter::new(Vec::new());
+        writer.write_index(&bi).unwrap();
+        writer.align().unwrap();
+        writer.align().unwrap();
+        writer.write_index(&BitIndex8::new(3).unwrap()).unwrap();
+        let buf = writer.into_inner().unwrap();
+        assert_eq!(vec![0b1111_0111, 0b111, 0b111], buf);
+
+        let mut reader = BitReader::new(&buf[..]);
+        assert_eq!(bi, reader.read_index(11).unwrap());
+        reader.align();
+        assert_eq!(BitIndex8::new(3).unwrap(), reader.read_index(3).unwrap());
+        assert!(reader.read_index::<BitIndex8>(9).is_err());
+    }
+
+    #[test]
+    fn drop_writes_pending() {
+        let mut buf = Vec::new();
+        {
+            let mut writer = BitWriter::new(&mut buf);
+            writer.write_bits(0x1FF, 9).unwrap();
+            writer.flush().unwrap();
+            assert_eq!(1, writer.inner_mut().len());
+        }
+        assert_eq!(vec![0xFF, 0b1], buf);
+    }
+}
@@ -0,0 +1,146 @@
+//! Variable-length integer codes on top of the bit streams.
+//!
+//! - unary: `n` one bits followed by a zero bit.
+//! - Elias gamma (`n >= 1`): as many zero bits as `n` has significant bits minus one, then `n`
+//!   most significant bit first.
+//! - Elias delta (`n >= 1`): the number of significant bits of `n` in gamma code, then `n`
+//!   without its leading one, most significant bit first.
+
+use super::{BitReader, BitWriter};
+use std::io::{self, Read, Write};
+
+impl<W: Write> BitWriter<W> {
+    pub fn write_unary(&mut self, n: u64) -> io::Result<()> {
+        for _ in 0..n {
+            self.write_bit(true)?;
+        }
+        self.write_bit(false)
+    }
+
+    pub fn write_gamma(&mut self, n: u64) -> io::Result<()> {
+        let len = significant_bits(n)?;
+        for _ in 1..len {
+            self.write_bit(false)?;
+        }
+        self.write_msb_first(n, len)
+    }
+
+    pub fn write_delta(&mut self, n: u64) -> io::Result<()> {
+        let len = significant_bits(n)?;
+        self.write_gamma(len as u64)?;
+        self.write_msb_first(n, len - 1)
+    }
+
+    fn write_msb_first(&mut self, n: u64, len: u8) -> io::Result<()> {
+        (0..len)
+            .rev()
+            .try_for_each(|bit_nb| self.write_bit(n >> bit_nb & 1 == 1))
+    }
+}
+
+impl<R: Read> BitReader<R> {
+    pub fn read_unary(&mut self) -> io::Result<u64> {
+        let mut n = 0;
+        while self.read_bit()? {
+            n += 1;
+        }
+        Ok(n)
+    }
+
+    pub fn read_gamma(&mut self) -> io::Result<u64> {
+        let mut len = 1;
+        while !self.read_bit()? {
+            len += 1;
+            if len > 64 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Elias gamma code longer than 64 bits",
+                ));
+            }
+        }
+        self.read_msb_first(1, len - 1)
+    }
+
+    pub fn read_delta(&mut self) -> io::Result<u64> {
+        let len = self.read_gamma()?;
+        if len > 64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Elias delta code longer than 64 bits",
+            ));
+        }
+        self.read_msb_first(1, len as u8 - 1)
+    }
+
+    fn read_msb_first(&mut self, mut n: u64, len: u8) -> io::Result<u64> {
+        for _ in 0..len {
+            n = n << 1 | self.read_bit()? as u64;
+        }
+        Ok(n)
+    }
+}
+
+fn significant_bits(n: u64) -> io::Result<u8> {
+    if n == 0 {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Elias codes can only encode numbers from 1",
+        ))
+    } else {
+        Ok(64 - n.leading_zeros() as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(f: impl FnOnce(&mut BitWriter<Vec<u8>>) -> io::Result<()>) -> Vec<u8> {
+        let mut writer = BitWriter::new(Vec::new());
+        f(&mut writer).unwrap();
+        writer.into_inner().unwrap()
+    }
+
+    #[test]
+    fn unary() {
+        assert_eq!(vec![0b0111], written(|w| w.write_unary(3)));
+        assert_eq!(vec![0b0], written(|w| w.write_unary(0)));
+        let buf = written(|w| (0..20).try_for_each(|n| w.write_unary(n)));
+        let mut reader = BitReader::new(&buf[..]);
+        assert!((0..20).all(|n| reader.read_unary().unwrap() == n));
+    }
+
+    #[test]
+    fn gamma() {
+        // 1 -> 1, 2 -> 010, 5 -> 00101, written in stream order
+        assert_eq!(vec![0b1], written(|w| w.write_gamma(1)));
+        assert_eq!(vec![0b010], written(|w| w.write_gamma(2)));
+        assert_eq!(vec![0b10100], written(|w| w.write_gamma(5)));
+        assert!(BitWriter::new(Vec::new()).write_gamma(0).is_err());
+
+        let numbers = [1, 2, 3, 7, 8, 1000, u64::MAX];
+        let buf = written(|w| numbers.iter().try_for_each(|&n| w.write_gamma(n)));
+        let mut reader = BitReader::new(&buf[..]);
+        assert!(numbers.iter().all(|&n| reader.read_gamma().unwrap() == n));
+    }
+
+    #[test]
+    fn delta() {
+        // 1 -> 1, 2 -> 0100, 17 -> 001010001
+        assert_eq!(vec![0b1], written(|w| w.write_delta(1)));
+        assert_eq!(vec![0b0010], written(|w| w.write_delta(2)));
+        assert_eq!(vec![0b0001_0100, 0b1], written(|w| w.write_delta(17)));
+
+        let numbers = [1, 2, 3, 17, 1 << 40, u64::MAX];
+        let buf = written(|w| numbers.iter().try_for_each(|&n| w.write_delta(n)));
+        let mut reader = BitReader::new(&buf[..]);
+        assert!(numbers.iter().all(|&n| reader.read_delta().unwrap() == n));
+    }
+
+    #[test]
+    fn corrupt() {
+        let zeros = [0u8; 10];
+        assert!(BitReader::new(&zeros[..]).read_gamma().is_err());
+        assert!(BitReader::new(&zeros[..]).read_delta().is_err());
+    }
+}
@@ -0,0 +1,12 @@
+//! Helpers shared by the test modules.
+
+/// A xorshift64 generator: reproducible pseudo-random words from a nonzero `seed`.
+pub(crate) fn xorshift(seed: u64) -> impl FnMut() -> u64 {
+    let mut state = seed;
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    }
+}
@@ -0,0 +1,156 @@
+use crate::BitIndex;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+
+/// A `BitIndex` addressed by a key type instead of raw bit numbers.
+///
+/// Keys convert to bit numbers with `Into<u8>` and back with `TryFrom<u8>`.
+/// Bits that do not convert back to a key are skipped when reading keys out.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TypedBitIndex<K, B> {
+    index: B,
+    key: PhantomData<K>,
+}
+
+impl<K, B> TypedBitIndex<K, B>
+where
+    K: Into<u8> + TryFrom<u8>,
+    B: BitIndex,
+{
+    pub fn new(nb_bits: u8) -> Result<Self, String> {
+        B::new(nb_bits).map(Self::from)
+    }
+
+    pub fn empty(nb_bits: u8) -> Result<Self, String> {
+        B::empty(nb_bits).map(Self::from)
+    }
+
+    pub fn index(&self) -> &B {
+        &self.index
+    }
+
+    pub fn into_index(self) -> B {
+        self.index
+    }
+
+    pub fn nb_elements(&self) -> u8 {
+        self.index.nb_elements()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn contains(&self, key: K) -> bool {
+        self.index.contains(key.into())
+    }
+
+    pub fn set(&mut self, key: K) {
+        self.index.set_bit(key.into());
+    }
+
+    pub fn unset(&mut self, key: K) {
+        self.index.unset_bit(key.into());
+    }
+
+    pub fn smallest(&self) -> Option<K> {
+        self.keys().next()
+    }
+
+    pub fn largest(&self) -> Option<K> {
+        self.find_key(B::pop_largest).map(|(_, key)| key)
+    }
+
+    pub fn pop_smallest(&mut self) -> Option<K> {
+        let res = self.find_key(B::pop_smallest);
+        res.map(|(bit_nb, key)| {
+            self.index.unset_bit(bit_nb);
+            key
+        })
+    }
+
+    pub fn pop_largest(&mut self) -> Option<K> {
+        let res = self.find_key(B::pop_largest);
+        res.map(|(bit_nb, key)| {
+            self.index.unset_bit(bit_nb);
+            key
+        })
+    }
+
+    /// The keys in increasing bit order.
+    pub fn keys(&self) -> impl Iterator<Item = K> {
+        let mut remaining = self.index;
+        std::iter::from_fn(move || remaining.pop_smallest())
+            .filter_map(|bit_nb| K::try_from(bit_nb).ok())
+    }
+
+    // The first bit popped from a copy of the index that converts to a key.
+    fn find_key(&self, mut pop: impl FnMut(&mut B) -> Option<u8>) -> Option<(u8, K)> {
+        let mut remaining = self.index;
+        std::iter::from_fn(move || pop(&mut remaining))
+            .find_map(|bit_nb| K::try_from(bit_nb).ok().map(|key| (bit_nb, key)))
+    }
+}
+
+impl<K, B: BitIndex> From<B> for TypedBitIndex<K, B> {
+    fn from(index: B) -> Self {
+        Self {
+            index,
+            key: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitIndex16;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct SlotId(u8);
+
+    impl From<SlotId> for u8 {
+        fn from(slot: SlotId) -> u8 {
+            slot.0
+        }
+    }
+
+    impl TryFrom<u8> for SlotId {
+        type Error = ();
+
+        fn try_from(bit_nb: u8) -> Result<Self, ()> {
+            if bit_nb & 1 == 0 {
+                Ok(SlotId(bit_nb))
+            } else {
+                Err(())
+            }
+        }
+    }
+
+    #[test]
+    fn typed_access() {
+        let mut slots = TypedBitIndex::<SlotId, BitIndex16>::empty(10).unwrap();
+        slots.set(SlotId(4));
+        slots.set(SlotId(8));
+        slots.set(SlotId(2));
+        assert!(slots.contains(SlotId(4)));
+        slots.unset(SlotId(4));
+        assert!(!slots.contains(SlotId(4)));
+        assert_eq!(vec![SlotId(2), SlotId(8)], slots.keys().collect::<Vec<_>>());
+        assert_eq!(Some(SlotId(8)), slots.pop_largest());
+        assert_eq!(Some(SlotId(2)), slots.pop_smallest());
+        assert_eq!(None, slots.pop_smallest());
+    }
+
+    #[test]
+    fn bits_without_key() {
+        let slots = TypedBitIndex::<SlotId, BitIndex16>::new(6).unwrap();
+        assert_eq!(Some(SlotId(0)), slots.smallest());
+        assert_eq!(Some(SlotId(4)), slots.largest());
+        assert_eq!(3, slots.keys().count());
+
+        let mut slots = slots;
+        assert_eq!(Some(SlotId(4)), slots.pop_largest());
+        assert_eq!(0b101111, slots.index().unwrap());
+    }
+}